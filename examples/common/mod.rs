@@ -0,0 +1,289 @@
+//! Layers shared between the examples: cities and intersections, reduced to not
+//! overlap each other, and the roads connecting the intersections.
+
+use ::rand::distr::uniform::SampleRange as _;
+use arrayvec::ArrayVec;
+use layer_proc_gen::{
+    debug::{Debug, DebugContent},
+    generic_layers::{
+        Euclidean, ReducedUniformPoint, Reducible, relative_neighborhood_graph, rng_for_point,
+    },
+    vec2::{Bounds, Line, Point2d},
+    *,
+};
+use std::{
+    hash::{Hash, Hasher},
+    ops::Range,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct City {
+    pub center: Point2d,
+    pub size: i64,
+    pub name: String,
+}
+
+impl From<Point2d> for City {
+    fn from(center: Point2d) -> Self {
+        let mut rng = rng_for_point::<0, _>(center, Seed(0));
+        let size = Self::RADIUS_RANGE.sample_single(&mut rng).unwrap();
+        let n = 10 * size / Self::RADIUS_RANGE.end;
+        City {
+            center,
+            size,
+            name: (0..(3..(n + 3)).sample_single(&mut rng).unwrap())
+                .map(|_| ('a'..='z').sample_single(&mut rng).unwrap())
+                .collect(),
+        }
+    }
+}
+
+impl Reducible for City {
+    const RADIUS_RANGE: Range<i64> = 100..500;
+
+    fn radius(&self) -> i64 {
+        self.size
+    }
+
+    fn position(&self) -> Point2d {
+        self.center
+    }
+
+    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
+        vec![
+            DebugContent::Circle {
+                center: self.center,
+                radius: self.size as f32,
+            },
+            DebugContent::Text {
+                pos: self.center,
+                label: self.name.clone(),
+            },
+        ]
+    }
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct Intersection(pub Point2d);
+
+impl From<Point2d> for Intersection {
+    fn from(value: Point2d) -> Self {
+        Self(value)
+    }
+}
+
+impl Reducible for Intersection {
+    const RADIUS_RANGE: Range<i64> = 50..51;
+
+    fn radius(&self) -> i64 {
+        15
+    }
+
+    fn position(&self) -> Point2d {
+        self.0
+    }
+}
+
+/// Removes locations that are too close to others
+#[derive(PartialEq, Debug, Clone, Default, Hash)]
+pub struct ReducedLocations {
+    pub points: ArrayVec<Point2d, 7>,
+    pub trees: ArrayVec<Point2d, 7>,
+}
+
+deps! {
+    #[derive(Default)]
+    pub struct ReducedLocationsDeps {
+        pub intersections: ReducedUniformPoint<Intersection, 6, 0>,
+        pub cities: Cities,
+    }
+}
+
+impl Chunk for ReducedLocations {
+    type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ReducedLocationsDeps;
+
+    const SIZE: Point2d<u8> = Point2d::splat(6);
+
+    fn compute_in_bounds(
+        ReducedLocationsDeps {
+            intersections,
+            cities,
+        }: &Self::Dependencies,
+        index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        let center = bounds.center();
+        let points = intersections
+            .get(index.same_index())
+            .points
+            .iter()
+            .map(|p| p.0)
+            .collect();
+        if cities
+            .get_range(Bounds::point(center).pad(Point2d::splat(City::RADIUS_RANGE.end)))
+            .all(|cities| {
+                cities
+                    .points
+                    .iter()
+                    .all(|city| center.manhattan_dist(city.center) > city.size)
+            })
+        {
+            ReducedLocations {
+                points: ArrayVec::default(),
+                trees: points,
+            }
+        } else {
+            ReducedLocations {
+                points,
+                trees: ArrayVec::default(),
+            }
+        }
+    }
+
+    fn clear(
+        ReducedLocationsDeps {
+            intersections,
+            cities,
+        }: &Self::Dependencies,
+        index: GridPoint<Self>,
+    ) {
+        cities.clear(Self::bounds(index).pad(Point2d::splat(City::RADIUS_RANGE.end)));
+        intersections.clear(Self::bounds(index));
+    }
+}
+
+impl Debug for ReducedLocations {
+    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
+        self.trees
+            .iter()
+            .map(|&center| DebugContent::Circle { center, radius: 8. })
+            .chain(
+                self.points
+                    .iter()
+                    .map(|&center| DebugContent::Circle { center, radius: 1. }),
+            )
+            .collect()
+    }
+}
+
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct Roads {
+    pub roads: Arc<Vec<Line>>,
+    /// Only filled if [RECORD_REJECTED_ROADS] was set when computing the chunk.
+    pub rejected: Arc<Vec<RejectedRoad>>,
+}
+
+impl Hash for Roads {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // The rejected roads are debug information, not part of the generated world.
+        self.roads.hash(state);
+    }
+}
+
+/// Record the candidate roads that the relative neighborhood graph rejected in [Roads::rejected],
+/// to visualize the decisions of the algorithm. Only affects chunks computed afterwards.
+pub static RECORD_REJECTED_ROADS: AtomicBool = AtomicBool::new(false);
+
+/// A candidate road that was not built because `veto` is closer to both of its ends
+/// than they are to each other.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct RejectedRoad {
+    pub road: Line,
+    pub veto: Point2d,
+}
+
+deps! {
+    pub struct RoadsDeps {
+        pub intersections: ReducedLocations,
+    }
+}
+
+impl Chunk for Roads {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = RoadsDeps;
+    const SIZE: Point2d<u8> = Point2d::splat(6);
+
+    fn compute_in_bounds(
+        RoadsDeps { intersections }: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        let center = index.same_index();
+        let points = ReducedLocations::moore_neighborhood(center)
+            .into_iter()
+            .flatten()
+            .map(|index| (index, intersections.get(index).points));
+        let mut rejected = vec![];
+        let roads = if RECORD_REJECTED_ROADS.load(Ordering::Relaxed) {
+            relative_neighborhood_graph(
+                Euclidean,
+                points,
+                center,
+                |&p| p,
+                |&a, &b| a.to(b),
+                |&a, &b, &veto| {
+                    rejected.push(RejectedRoad {
+                        road: a.to(b),
+                        veto,
+                    })
+                },
+            )
+        } else {
+            relative_neighborhood_graph(
+                Euclidean,
+                points,
+                center,
+                |&p| p,
+                |&a, &b| a.to(b),
+                |_, _, _| {},
+            )
+        }
+        .into();
+        Roads {
+            roads,
+            rejected: rejected.into(),
+        }
+    }
+
+    fn clear(RoadsDeps { intersections }: &Self::Dependencies, index: GridPoint<Self>) {
+        intersections.clear(Self::vision_range(Self::bounds(index)));
+    }
+}
+
+impl Debug for Roads {
+    /// Rejected roads are drawn dashed, with a small circle at the point that vetoed them.
+    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
+        const DASHES: u8 = 8;
+        let rejected = self.rejected.iter().flat_map(|rejected| {
+            (0..DASHES)
+                .map(|i| {
+                    let t = |i: u8| f64::from(i) / f64::from(DASHES * 2);
+                    DebugContent::Line(
+                        rejected
+                            .road
+                            .point_at(t(i * 2))
+                            .to(rejected.road.point_at(t(i * 2 + 1))),
+                    )
+                })
+                .chain([DebugContent::Circle {
+                    center: rejected.veto,
+                    radius: 2.,
+                }])
+        });
+        self.roads
+            .iter()
+            .copied()
+            .map(DebugContent::from)
+            .chain(rejected)
+            .collect()
+    }
+}
+
+pub type Cities = ReducedUniformPoint<City, 11, 1>;
@@ -0,0 +1,72 @@
+//! Generates a fixed region of the road network from the `infinite_roads` example
+//! and prints it as ASCII art. Needs no window or GPU, so it also works in CI.
+
+use common::*;
+use layer_proc_gen::*;
+use vec2::{Bounds, Point2d};
+
+mod common;
+
+/// How many world units a single character of the map covers.
+const SCALE: i64 = 12;
+const WIDTH: usize = 80;
+const HEIGHT: usize = 40;
+
+fn main() {
+    let mut builder = builder::LayerBuilder::new().with(Seed::default());
+    let locations = builder.share::<ReducedLocations>().unwrap();
+    let roads = builder.build::<Roads>().unwrap();
+
+    // Center the region on the largest nearby city, the space between cities only has trees.
+    let city = locations
+        .cities
+        .get_grid_range(
+            Bounds::point(Point2d::splat(GridIndex::from_raw(0)))
+                .pad(Point2d::splat(GridIndex::from_raw(2))),
+        )
+        .flat_map(|c| c.points.into_iter())
+        .max_by_key(|city| city.size)
+        .expect("no cities in a 5x5 grid");
+    let region =
+        Bounds::point(city.center).pad(Point2d::new(WIDTH as i64, HEIGHT as i64) * SCALE / 2);
+    roads.ensure_loaded_in_bounds(region);
+
+    let mut map = vec![[' '; WIDTH]; HEIGHT];
+    let mut plot = |pos: Point2d, c| {
+        let pos = (pos - region.min) / SCALE;
+        if let (Ok(x), Ok(y)) = (usize::try_from(pos.x), usize::try_from(pos.y)) {
+            if let Some(cell) = map.get_mut(y).and_then(|row| row.get_mut(x)) {
+                *cell = c;
+            }
+        }
+    };
+
+    let mut road_count = 0;
+    for chunk in roads.get_range(region) {
+        for road in chunk.roads.iter() {
+            road_count += 1;
+            let dir = road.end - road.start;
+            let steps = dir.x.abs().max(dir.y.abs()) / SCALE + 1;
+            for i in 0..=steps {
+                plot(road.start + dir * i / steps, '#');
+            }
+        }
+    }
+    let mut tree_count = 0;
+    for chunk in locations.get_range(region) {
+        for &tree in &chunk.trees {
+            tree_count += 1;
+            plot(tree, 'T');
+        }
+        for &intersection in &chunk.points {
+            plot(intersection, 'o');
+        }
+    }
+
+    println!("region around {}: {region:?}", city.name);
+    println!("roads: {road_count}");
+    println!("trees: {tree_count}");
+    for row in map {
+        println!("{}", row.iter().collect::<String>());
+    }
+}
@@ -1,264 +1,20 @@
-use ::rand::distr::uniform::SampleRange as _;
-use arrayvec::ArrayVec;
+use common::*;
 use debug::{Debug, DebugContent, DynLayer};
-use generic_layers::{ReducedUniformPoint, Reducible, rng_for_point};
 use macroquad::prelude::*;
 use miniquad::window::screen_size;
 use std::{
-    borrow::Borrow,
     collections::{BTreeMap, HashMap},
     f32::consts::{FRAC_PI_2, PI},
     num::NonZeroU8,
-    ops::Range,
-    sync::Arc,
+    sync::{Arc, atomic::Ordering},
 };
 
+use generic_layers::{Euclidean, relative_neighborhood_graph};
 use layer_proc_gen::*;
 use rigid2d::Body;
 use vec2::{Bounds, Line, Num, Point2d};
 
-#[derive(PartialEq, Debug, Clone, Default)]
-struct City {
-    center: Point2d,
-    size: i64,
-    name: String,
-}
-
-impl From<Point2d> for City {
-    fn from(center: Point2d) -> Self {
-        let mut rng = rng_for_point::<0, _>(center, Seed(0));
-        let size = Self::RADIUS_RANGE.sample_single(&mut rng).unwrap();
-        let n = 10 * size as i64 / Self::RADIUS_RANGE.end as i64;
-        City {
-            center,
-            size,
-            name: (0..(3..(n + 3)).sample_single(&mut rng).unwrap())
-                .map(|_| ('a'..='z').sample_single(&mut rng).unwrap())
-                .collect(),
-        }
-    }
-}
-
-impl Reducible for City {
-    const RADIUS_RANGE: Range<i64> = 100..500;
-
-    fn radius(&self) -> i64 {
-        self.size
-    }
-
-    fn position(&self) -> Point2d {
-        self.center
-    }
-
-    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
-        vec![
-            DebugContent::Circle {
-                center: self.center,
-                radius: self.size as f32,
-            },
-            DebugContent::Text {
-                pos: self.center,
-                label: self.name.clone(),
-            },
-        ]
-    }
-}
-
-#[derive(Clone, PartialEq, Default)]
-struct Intersection(Point2d);
-
-impl From<Point2d> for Intersection {
-    fn from(value: Point2d) -> Self {
-        Self(value)
-    }
-}
-
-impl Reducible for Intersection {
-    const RADIUS_RANGE: Range<i64> = 50..51;
-
-    fn radius(&self) -> i64 {
-        15
-    }
-
-    fn position(&self) -> Point2d {
-        self.0
-    }
-}
-
-/// Removes locations that are too close to others
-#[derive(PartialEq, Debug, Clone, Default)]
-struct ReducedLocations {
-    points: ArrayVec<Point2d, 7>,
-    trees: ArrayVec<Point2d, 7>,
-}
-
-deps! {
-    #[derive(Default)]
-    struct ReducedLocationsDeps {
-        intersections: ReducedUniformPoint<Intersection, 6, 0>,
-        cities: Cities,
-    }
-}
-
-impl Chunk for ReducedLocations {
-    type LayerStore<T> = Arc<T>;
-    type Dependencies = ReducedLocationsDeps;
-
-    const SIZE: Point2d<u8> = Point2d::splat(6);
-
-    fn compute(
-        ReducedLocationsDeps {
-            intersections,
-            cities,
-        }: &Self::Dependencies,
-        index: GridPoint<Self>,
-    ) -> Self {
-        let bounds = Self::bounds(index);
-        let center = bounds.center();
-        let points = intersections
-            .get(index.into_same_chunk_size())
-            .points
-            .iter()
-            .map(|p| p.0)
-            .collect();
-        if cities
-            .get_range(Bounds::point(center).pad(Point2d::splat(City::RADIUS_RANGE.end)))
-            .all(|cities| {
-                cities
-                    .points
-                    .iter()
-                    .all(|city| center.manhattan_dist(city.center) > city.size)
-            })
-        {
-            ReducedLocations {
-                points: ArrayVec::default(),
-                trees: points,
-            }
-        } else {
-            ReducedLocations {
-                points,
-                trees: ArrayVec::default(),
-            }
-        }
-    }
-
-    fn clear(
-        ReducedLocationsDeps {
-            intersections,
-            cities,
-        }: &Self::Dependencies,
-        index: GridPoint<Self>,
-    ) {
-        cities.clear(Self::bounds(index).pad(Point2d::splat(City::RADIUS_RANGE.end)));
-        intersections.clear(Self::bounds(index));
-    }
-}
-
-impl Debug for ReducedLocations {
-    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
-        self.trees
-            .iter()
-            .map(|&center| DebugContent::Circle { center, radius: 8. })
-            .chain(
-                self.points
-                    .iter()
-                    .map(|&center| DebugContent::Circle { center, radius: 1. }),
-            )
-            .collect()
-    }
-}
-
-#[derive(PartialEq, Debug, Default, Clone)]
-struct Roads {
-    roads: Arc<Vec<Line>>,
-}
-
-deps! {
-    struct RoadsDeps {
-        intersections: ReducedLocations,
-    }
-}
-
-impl Chunk for Roads {
-    type LayerStore<T> = T;
-    type Dependencies = RoadsDeps;
-    const SIZE: Point2d<u8> = Point2d::splat(6);
-
-    fn compute(RoadsDeps { intersections }: &Self::Dependencies, index: GridPoint<Self>) -> Self {
-        let roads = gen_roads(
-            intersections
-                .get_moore_neighborhood(index.into_same_chunk_size())
-                .into_iter()
-                .flatten()
-                .map(|chunk| chunk.points),
-            |&p| p,
-            |&a, &b| a.to(b),
-        )
-        .into();
-        Roads { roads }
-    }
-
-    fn clear(RoadsDeps { intersections }: &Self::Dependencies, index: GridPoint<Self>) {
-        intersections.clear(Self::vision_range(Self::bounds(index)));
-    }
-}
-
-impl Debug for Roads {
-    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
-        self.roads.iter().copied().map(DebugContent::from).collect()
-    }
-}
-
-fn gen_roads<T: Clone, U>(
-    chunks: impl Iterator<Item = impl Borrow<[T]>>,
-    get_point: impl Fn(&T) -> Point2d,
-    mk: impl Fn(&T, &T) -> U,
-) -> Vec<U> {
-    let mut roads = vec![];
-    let mut points: ArrayVec<T, { 3 * 9 }> = ArrayVec::new();
-    let mut start = usize::MAX;
-    let mut n = usize::MAX;
-    for (i, grid) in chunks.enumerate() {
-        let grid = grid.borrow();
-        if i == 4 {
-            start = points.len();
-            n = grid.len();
-        }
-        points.extend(grid.iter().cloned());
-    }
-    // We only care about the roads starting from the center grid cell, as the others are not necessarily correct,
-    // or will be computed by the other grid cells.
-    // The others may connect the outer edges of the current grid range and thus connect roads that
-    // don't satisfy the algorithm.
-    // This algorithm is https://en.m.wikipedia.org/wiki/Relative_neighborhood_graph adjusted for
-    // grid-based computation. It's a brute force implementation, but I think that is faster than going through
-    // a Delaunay triangulation first, as instead of (3*9)^3 = 19683 inner loop iterations we have only
-    // 3 * (2 + 1 + 3*4) * 3*9 = 1215
-    // FIXME: cache distance computations as we do them, we can save 1215-(3*9^3)/2 = 850 distance computations (70%) and figure
-    // out how to cache them across grid cells (along with removing them from the cache when they aren't needed anymore)
-    // as the neighboring cells will be redoing the same distance computations.
-    for (i, a_val) in points.iter().enumerate().skip(start).take(n) {
-        let a = get_point(a_val);
-        for b_val in points.iter().skip(i + 1) {
-            let b = get_point(b_val);
-            let dist = a.dist_squared(b);
-            if points.iter().all(|c| {
-                let c = get_point(c);
-                if a == c || b == c {
-                    return true;
-                }
-                // FIXME: make cheaper by already bailing if `x*x` is larger than dist,
-                // to avoid computing `y*y`.
-                let a_dist = a.dist_squared(c);
-                let b_dist = c.dist_squared(b);
-                dist < a_dist || dist < b_dist
-            }) {
-                roads.push(mk(a_val, b_val))
-            }
-        }
-    }
-    roads
-}
+mod common;
 
 #[derive(PartialEq, Debug, Clone)]
 
@@ -275,8 +31,6 @@ struct Highways {
     roads: Arc<Vec<Highway>>,
 }
 
-type Cities = ReducedUniformPoint<City, 11, 1>;
-
 deps! {
     struct HighwayDeps {
         intersections: ReducedLocations,
@@ -285,17 +39,23 @@ deps! {
 
 impl Chunk for Highways {
     type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
     type Dependencies = HighwayDeps;
     const SIZE: Point2d<u8> = Cities::SIZE;
 
-    fn compute(HighwayDeps { intersections }: &Self::Dependencies, index: GridPoint<Self>) -> Self {
-        let roads = gen_roads(
-            intersections
-                .cities
-                .get_moore_neighborhood(index.into_same_chunk_size())
+    fn compute_in_bounds(
+        HighwayDeps { intersections }: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        let center = index.same_index();
+        let roads = relative_neighborhood_graph(
+            Euclidean,
+            Cities::moore_neighborhood(center)
                 .into_iter()
                 .flatten()
-                .map(|chunk| chunk.points),
+                .map(|index| (index, intersections.cities.get(index).points)),
+            center,
             |p| p.center,
             |a, b| {
                 (
@@ -306,6 +66,7 @@ impl Chunk for Highways {
                     b.name.clone(),
                 )
             },
+            |_, _, _| {},
         );
 
         let roads = roads
@@ -412,16 +173,13 @@ impl Player {
         }
     }
 
-    pub fn vision_range<C: Chunk>(&self, vision_range: Vec2) -> Bounds {
-        let padding = vision_range.abs().ceil().as_i64vec2();
-        let bounds = Bounds::point(self.pos())
-            // pad by the screen area, so everything that will get rendered is within the vision range
-            .pad(Point2d::new(padding.x, padding.y));
-        C::vision_range(bounds)
-    }
-
-    pub fn grid_vision_range<C: Chunk>(&self, vision_range: Vec2) -> Bounds<GridIndex<C>> {
-        C::bounds_to_grid(self.vision_range::<C>(vision_range))
+    /// The area visible on screen, where `screen` is half the screen size in world coordinates.
+    pub fn vision_range(&self, screen: Vec2) -> VisionRange {
+        let pos = self.car.body.position;
+        VisionRange::new(
+            Point2d::new(pos.x.into(), pos.y.into()),
+            Point2d::new(screen.x.into(), screen.y.into()),
+        )
     }
 }
 
@@ -443,22 +201,24 @@ deps! {
 
 impl Chunk for PlayerView {
     type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
 
     type Dependencies = PlayerDeps;
 
-    fn compute(
+    fn compute_in_bounds(
         PlayerDeps {
             city_roads,
             highways,
         }: &Self::Dependencies,
-        index: GridPoint<Self>,
+        _index: GridPoint<Self>,
+        bounds: Bounds,
     ) -> Self {
         let mut roads = vec![];
         let mut trees = vec![];
 
         let padding = screen_padding().as_i64vec2();
         let padding = Point2d::new(padding.x, padding.y);
-        let bounds = Self::bounds(index).pad(padding);
+        let bounds = bounds.pad(padding);
         let grid_vision_range = Roads::bounds_to_grid(Roads::vision_range(bounds));
         let highway_vision_range = Highways::bounds_to_grid(Highways::vision_range(bounds));
 
@@ -477,11 +237,7 @@ impl Chunk for PlayerView {
             roads.extend_from_slice(&highways.get(index).roads);
         }
         for index in grid_vision_range.iter() {
-            for &tree in &highways
-                .intersections
-                .get(index.into_same_chunk_size())
-                .trees
-            {
+            for &tree in &highways.intersections.get(index.same_index()).trees {
                 trees.push(Tree { pos: tree });
             }
         }
@@ -547,7 +303,7 @@ async fn main() {
         .find_map(|c| c.roads.iter().copied().next())
         .expect("you wont the lottery, no roads in a city");
     player.car.body.position = vec2(start_road.start.x as f32, start_road.start.y as f32);
-    let dir = start_road.end - start_road.end;
+    let dir = start_road.end - start_road.start;
     player.car.body.rotation = vec2(dir.x as f32, dir.y as f32).to_angle() + FRAC_PI_2;
 
     let mut smooth_cam_speed = 0.0;
@@ -587,6 +343,9 @@ async fn main() {
         if is_key_pressed(KeyCode::F2) {
             debug_chunks = !debug_chunks;
         }
+        if is_key_pressed(KeyCode::F5) {
+            RECORD_REJECTED_ROADS.fetch_xor(true, Ordering::Relaxed);
+        }
 
         smooth_cam_speed = smooth_cam_speed * 0.99 + player.car.body.velocity.length() / 30. * 0.01;
         let max_zoom_in = f32::from(player.max_zoom_in.get());
@@ -594,8 +353,10 @@ async fn main() {
         smooth_cam_speed = smooth_cam_speed.clamp(0.0, max_zoom_in);
 
         let standard_zoom = Vec2::from(screen_size()).recip() * 4.;
-        let mut camera = Camera2D::default();
-        camera.zoom = standard_zoom * (max_zoom_in + 1.0 / max_zoom_out - smooth_cam_speed);
+        let mut camera = Camera2D {
+            zoom: standard_zoom * (max_zoom_in + 1.0 / max_zoom_out - smooth_cam_speed),
+            ..Default::default()
+        };
         camera.zoom /= debug_zoom;
         set_camera(&camera);
         camera.zoom *= debug_zoom;
@@ -610,10 +371,10 @@ async fn main() {
             let min = point2screen(bounds.min);
             let max = point2screen(bounds.max);
             draw_rectangle_lines(
-                min.x as f32,
-                min.y as f32,
-                (max.x - min.x) as f32,
-                (max.y - min.y) as f32,
+                min.x,
+                min.y,
+                max.x - min.x,
+                max.y - min.y,
                 debug_zoom,
                 color,
             );
@@ -625,13 +386,7 @@ async fn main() {
             }
             let min = point2screen(bounds.min);
             let max = point2screen(bounds.max);
-            draw_rectangle(
-                min.x as f32,
-                min.y as f32,
-                (max.x - min.x) as f32,
-                (max.y - min.y) as f32,
-                color,
-            );
+            draw_rectangle(min.x, min.y, max.x - min.x, max.y - min.y, color);
         };
 
         let draw_line = |line: Line, thickness, color| {
@@ -735,6 +490,19 @@ async fn main() {
                 let pos = point2screen(pos);
                 draw_multiline_text(&label, pos.x, pos.y, 100., Some(1.), color);
             }
+            DebugContent::Polygon { points, fill } => {
+                let corners: Vec<_> = points.iter().map(|&p| point2screen(p)).collect();
+                if fill {
+                    // A triangle fan, which is only correct for convex polygons, but good enough for debugging.
+                    for pair in corners[1..].windows(2) {
+                        draw_triangle(corners[0], pair[0], pair[1], color);
+                    }
+                }
+                for (i, &start) in points.iter().enumerate() {
+                    let end = points[(i + 1) % points.len()];
+                    draw_line(start.to(end), thickness, color);
+                }
+            }
         };
         let draw_layer_debug = |layer: &dyn DynLayer, color| {
             for (current_chunk, chunk) in layer.iter_all_loaded() {
@@ -760,16 +528,19 @@ async fn main() {
                 PURPLE,
             );
 
-            let vision_range = player.vision_range::<Roads>(padding);
-            draw_bounds(vision_range, PURPLE);
+            let vision_range = player.vision_range(padding);
+            draw_bounds(vision_range.bounds_for::<Roads>(), PURPLE);
 
-            for index in player.grid_vision_range(padding).iter() {
+            for index in vision_range.grid_bounds::<Roads>().iter() {
                 let current_chunk = Roads::bounds(index);
-                draw_bounds(current_chunk, PURPLE);
+                let [r, g, b] = index.debug_color();
+                draw_bounds(current_chunk, Color::from_rgba(r, g, b, 255));
             }
-            let mut overlay_camera = Camera2D::default();
-            overlay_camera.zoom = standard_zoom / 4.;
-            overlay_camera.offset = vec2(-1., 1.);
+            let overlay_camera = Camera2D {
+                zoom: standard_zoom / 4.,
+                offset: vec2(-1., 1.),
+                ..Default::default()
+            };
             set_camera(&overlay_camera);
             draw_text(&format!("fps: {}", get_fps()), 0., 30., 30., WHITE);
             draw_text(
@@ -989,6 +760,16 @@ async fn render_3d_layers(top_layers: Vec<&dyn DynLayer>) {
                             }
                         }
                         DebugContent::Text { .. } => {}
+                        DebugContent::Polygon { points, .. } => {
+                            for (i, &start) in points.iter().enumerate() {
+                                let end = points[(i + 1) % points.len()];
+                                draw_line_3d(
+                                    pos + point_to_3d(start),
+                                    pos + point_to_3d(end),
+                                    color,
+                                );
+                            }
+                        }
                     }
                 }
             }
@@ -1120,17 +901,15 @@ impl Car {
             -STEERING_SPEED
         } else if actions.right {
             STEERING_SPEED
+        } else if self.steering.abs() < STEERING_SPEED {
+            -self.steering
         } else {
-            if self.steering.abs() < STEERING_SPEED {
-                -self.steering
-            } else {
-                -self.steering.signum() * STEERING_SPEED
-            }
+            -self.steering.signum() * STEERING_SPEED
         };
         self.steering = self
             .steering
             .clamp((-self.steering_limit).into(), self.steering_limit.into());
-        let steer_dir = f32::from(self.steering).to_radians();
+        let steer_dir = self.steering.to_radians();
 
         self.braking = actions.hand_brake;
         self.reversing = actions.reverse;
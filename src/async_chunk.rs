@@ -0,0 +1,85 @@
+use std::{
+    borrow::Borrow as _,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{Bounds, Chunk, ChunkExt as _, ChunkGrid as _, GridPoint, Layer};
+
+/// Chunks that need to wait for something while being computed, e.g. base data (heightmaps, biome tables)
+/// that is read from disk or fetched over the network.
+///
+/// Use [Layer::ensure_loaded_in_bounds_async] to compute the chunks of an area concurrently.
+/// Afterwards they are in the cache like any other chunks. Requesting a chunk that isn't cached
+/// still goes through the synchronous [Chunk::compute], so it should also work, e.g. by blocking.
+pub trait AsyncChunk: Chunk {
+    /// Same as [Chunk::compute], but can wait for other things without blocking the thread.
+    fn compute_async(
+        layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+    ) -> impl Future<Output = Self>;
+}
+
+impl<C: AsyncChunk> Layer<C> {
+    /// Like [Layer::ensure_loaded_in_bounds], but computes all chunks that aren't cached yet
+    /// concurrently via [AsyncChunk::compute_async].
+    ///
+    /// Each chunk is only computed once per call. Concurrent calls for overlapping areas each compute
+    /// the chunks that weren't cached when they started, and the last one to finish stores its result.
+    pub async fn ensure_loaded_in_bounds_async(&self, chunk_bounds: Bounds) {
+        let indices = C::bounds_to_grid(chunk_bounds);
+        let deps = &self.layer.borrow().1;
+        let pending: Vec<_> = indices
+            .iter_spiral(indices.center())
+            .filter(|&index| !self.layer.borrow().0.contains(index))
+            .map(|index| {
+                Box::pin(async move { (index, C::compute_async(deps, index).await) })
+                    as Pin<Box<dyn Future<Output = _> + '_>>
+            })
+            .collect();
+        for (index, chunk) in JoinAll::new(pending).await {
+            self.layer.borrow().0.set(deps, index, chunk);
+        }
+    }
+}
+
+/// Polls all futures whenever it is polled, until all of them are done.
+/// Returns their results in the order of the futures.
+struct JoinAll<'a, T> {
+    pending: Vec<Option<Pin<Box<dyn Future<Output = T> + 'a>>>>,
+    done: Vec<Option<T>>,
+}
+
+impl<'a, T> JoinAll<'a, T> {
+    fn new(pending: Vec<Pin<Box<dyn Future<Output = T> + 'a>>>) -> Self {
+        Self {
+            done: pending.iter().map(|_| None).collect(),
+            pending: pending.into_iter().map(Some).collect(),
+        }
+    }
+}
+
+// The futures are boxed and the results are never pinned, so moving out of the pin is fine.
+impl<T> Unpin for JoinAll<'_, T> {}
+
+impl<T> Future for JoinAll<'_, T> {
+    type Output = Vec<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        for (future, done) in this.pending.iter_mut().zip(&mut this.done) {
+            if let Some(fut) = future {
+                if let Poll::Ready(value) = fut.as_mut().poll(cx) {
+                    *done = Some(value);
+                    *future = None;
+                }
+            }
+        }
+        if this.pending.iter().all(Option::is_none) {
+            Poll::Ready(this.done.drain(..).map(Option::unwrap).collect())
+        } else {
+            Poll::Pending
+        }
+    }
+}
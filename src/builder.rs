@@ -0,0 +1,172 @@
+//! Creating a [Layer] together with all of its dependencies, sharing the dependency
+//! layers that are used by multiple other layers.
+
+use std::{
+    any::{Any, TypeId, type_name},
+    collections::HashMap,
+};
+
+use crate::{Chunk, Dependencies, Layer, Seed, generic_layers::LayerConfig};
+
+/// Collects settings like the [Seed] and the layers that should be shared, and
+/// creates layers with all their dependencies from them, see [LayerBuilder::build].
+///
+/// ```
+/// # use layer_proc_gen::{*, builder::LayerBuilder, generic_layers::*};
+/// # #[derive(Clone, PartialEq, Debug)]
+/// # struct Town(Point2d);
+/// # impl From<Point2d> for Town { fn from(p: Point2d) -> Self { Self(p) } }
+/// # impl Reducible for Town {
+/// #     const RADIUS_RANGE: std::ops::Range<i64> = 5..6;
+/// #     fn radius(&self) -> i64 { 5 }
+/// #     fn position(&self) -> Point2d { self.0 }
+/// # }
+/// type Towns = ReducedUniformPoint<Town, 6, 0>;
+/// let mut builder = LayerBuilder::new().with(Seed(42));
+/// let towns = builder.build::<Towns>().unwrap();
+/// towns.get(point!(3, -4));
+/// ```
+#[derive(Default)]
+pub struct LayerBuilder {
+    /// Each value is a `Box<dyn Fn() -> T>` for the `T` of the key, creating clones of a registered value.
+    values: HashMap<TypeId, Box<dyn Any>>,
+    /// The type names of the chunks whose layers are currently being built, for error messages.
+    building: Vec<&'static str>,
+}
+
+impl LayerBuilder {
+    /// A builder without any settings or shared layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a value that [Build] implementations can look up by type, e.g. the [Seed].
+    /// Registering a [Layer] (with a [Chunk::LayerStore] of `Arc<T>`) makes all layers
+    /// built afterwards share it instead of building their own.
+    /// Replaces any value of the same type registered before.
+    pub fn with<T: Clone + 'static>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Same as [LayerBuilder::with], but for builders that are borrowed.
+    pub fn insert<T: Clone + 'static>(&mut self, value: T) {
+        let make: Box<dyn Fn() -> T> = Box::new(move || value.clone());
+        self.values.insert(TypeId::of::<T>(), Box::new(make));
+    }
+
+    /// A clone of the value of type `T` registered via [LayerBuilder::with] or [LayerBuilder::share].
+    pub fn get<T: 'static>(&self) -> Option<T> {
+        let make = self.values.get(&TypeId::of::<T>())?;
+        Some(make.downcast_ref::<Box<dyn Fn() -> T>>().unwrap()())
+    }
+
+    /// Same as [LayerBuilder::get], but reports which layer needed the value if it is missing.
+    pub fn require<T: 'static>(&self) -> Result<T, BuildError> {
+        self.get().ok_or_else(|| BuildError::Missing {
+            dependency: type_name::<T>(),
+            needed_by: self.building.last().copied(),
+        })
+    }
+
+    /// Create a layer of `C` and all of its dependencies.
+    ///
+    /// Registered layers are used as they are. All other layers are created from scratch,
+    /// so layers that are needed by multiple other layers exist multiple times, each with
+    /// its own cache. Use [LayerBuilder::share] to avoid that.
+    pub fn build<C: Chunk>(&mut self) -> Result<Layer<C>, BuildError>
+    where
+        C::Dependencies: Build,
+    {
+        if let Some(layer) = self.get::<Layer<C>>() {
+            return Ok(layer);
+        }
+        self.building.push(type_name::<C>());
+        let deps = C::Dependencies::build(self);
+        self.building.pop();
+        Ok(Layer::new(deps?))
+    }
+
+    /// Same as [LayerBuilder::build], but also registers the layer, so all layers built
+    /// afterwards share it.
+    pub fn share<C: Chunk>(&mut self) -> Result<Layer<C>, BuildError>
+    where
+        C::Dependencies: Build,
+        Layer<C>: Clone,
+    {
+        let layer = self.build::<C>()?;
+        self.insert(layer.clone());
+        Ok(layer)
+    }
+}
+
+/// [Dependencies] that a [LayerBuilder] can create.
+///
+/// Implemented for [Seed] and [LayerConfig] (which need to be registered via [LayerBuilder::with]),
+/// `()` and layers whose dependencies implement it. Structs created via the [deps] macro
+/// implement it if all their layers do.
+pub trait Build: Dependencies + Sized {
+    /// Create the dependencies from the values registered in `builder`.
+    fn build(builder: &mut LayerBuilder) -> Result<Self, BuildError>;
+}
+
+impl Build for () {
+    fn build(_builder: &mut LayerBuilder) -> Result<Self, BuildError> {
+        Ok(())
+    }
+}
+
+impl Build for Seed {
+    fn build(builder: &mut LayerBuilder) -> Result<Self, BuildError> {
+        builder.require()
+    }
+}
+
+impl Build for LayerConfig {
+    fn build(builder: &mut LayerBuilder) -> Result<Self, BuildError> {
+        builder.require()
+    }
+}
+
+impl<C: Chunk + crate::debug::Debug> Build for Layer<C>
+where
+    C::Dependencies: Build,
+{
+    fn build(builder: &mut LayerBuilder) -> Result<Self, BuildError> {
+        builder.build()
+    }
+}
+
+/// Errors that [LayerBuilder] reports instead of creating a layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// A value that can't be built needs to be registered via [LayerBuilder::with].
+    Missing {
+        /// The type name of the value.
+        dependency: &'static str,
+        /// The type name of the chunk whose dependencies contain the value,
+        /// or `None` if the value was requested directly.
+        needed_by: Option<&'static str>,
+    },
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::Missing {
+                dependency,
+                needed_by: Some(layer),
+            } => write!(
+                f,
+                "{dependency} is needed by {layer}, but was not registered in the builder"
+            ),
+            BuildError::Missing {
+                dependency,
+                needed_by: None,
+            } => write!(f, "{dependency} was not registered in the builder"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
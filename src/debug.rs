@@ -1,10 +1,12 @@
 //! Various helpers for viewing layers and their data without knowing the exact structure and contents
 
-use std::{any::TypeId, borrow::Borrow as _};
+use std::{
+    any::{Any, TypeId},
+    borrow::Borrow as _,
+};
 
 use crate::{
-    Chunk, ChunkExt as _, Dependencies as _, Layer,
-    rolling_grid::RollingGrid,
+    Chunk, ChunkExt as _, ChunkGrid as _, Dependencies as _, Layer,
     vec2::{Bounds, Line, Point2d},
 };
 
@@ -38,6 +40,13 @@ pub enum DebugContent {
         /// Actual message of the text (can have newlines).
         label: String,
     },
+    /// A closed polygon, e.g. the outline of a lake or district.
+    Polygon {
+        /// The corners of the polygon. The last one is connected to the first one.
+        points: Vec<Point2d>,
+        /// Whether to fill the area instead of only drawing the outline.
+        fill: bool,
+    },
 }
 
 impl From<Line> for DebugContent {
@@ -46,6 +55,34 @@ impl From<Line> for DebugContent {
     }
 }
 
+/// A dependency chunk that was read while computing a chunk, see [Layer::inspect_deps].
+pub struct DependencySnapshot {
+    /// The type name of the dependency chunk.
+    pub layer: &'static str,
+    /// The position of the dependency chunk in its layer's grid.
+    pub index: Point2d,
+    /// The world coordinates of the dependency chunk.
+    pub bounds: Bounds,
+    /// A copy of the dependency chunk, or `None` if it was not loaded.
+    pub chunk: Option<Box<dyn Any>>,
+}
+
+impl DependencySnapshot {
+    pub(crate) fn new<C: Chunk>(index: crate::GridPoint<C>, chunk: Option<C>) -> Self {
+        Self {
+            layer: std::any::type_name::<C>(),
+            index: index.map(|i| i.0),
+            bounds: C::bounds(index),
+            chunk: chunk.map(|chunk| Box::new(chunk) as Box<dyn Any>),
+        }
+    }
+
+    /// Get the dependency chunk if it was loaded and is of type `C`.
+    pub fn downcast<C: Chunk>(&self) -> Option<&C> {
+        self.chunk.as_ref()?.downcast_ref()
+    }
+}
+
 /// Can point to any layer and allows programatic access to dependencies and chunks.
 /// Implemented for [Layer]. You should implement this if you manually implement [Dependencies](super::Dependencies).
 pub trait DynLayer {
@@ -61,6 +98,12 @@ pub trait DynLayer {
 
     /// A shortened version of the type name of the layer and its generic parameters.
     fn name(&self) -> String;
+
+    /// See [Layer::version]. Defaults to zero, so the chunks depending on this layer are never
+    /// recomputed because of it.
+    fn version(&self) -> u64 {
+        0
+    }
 }
 
 impl<C: Chunk + Debug> DynLayer for Layer<C> {
@@ -84,30 +127,151 @@ impl<C: Chunk + Debug> DynLayer for Layer<C> {
     }
 
     fn ident(&self) -> (usize, TypeId) {
-        let ptr: *const RollingGrid<C> = &self.layer.borrow().0;
+        let ptr: *const C::Grid = &self.layer.borrow().0;
         (ptr as usize, TypeId::of::<Self>())
     }
 
     fn name(&self) -> String {
-        let mut name = std::any::type_name::<C>().to_owned();
-        let mut start = 0;
-        loop {
-            while let Some((pos, _)) = name[start..]
-                .char_indices()
-                .take_while(|&(_, c)| matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | ':'))
-                .find(|&(_, c)| c == ':')
-            {
-                name.replace_range(start..(start + pos + 2), "");
+        short_type_name::<C>()
+    }
+
+    fn version(&self) -> u64 {
+        Layer::version(self)
+    }
+}
+
+/// The type name of `T` and its generic parameters, without module paths.
+pub(crate) fn short_type_name<T: ?Sized>() -> String {
+    let mut name = std::any::type_name::<T>().to_owned();
+    let mut start = 0;
+    loop {
+        while let Some((pos, _)) = name[start..]
+            .char_indices()
+            .take_while(|&(_, c)| matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | ':'))
+            .find(|&(_, c)| c == ':')
+        {
+            name.replace_range(start..(start + pos + 2), "");
+        }
+        if let Some((next, c)) = name[start..]
+            .char_indices()
+            .find(|&(_, c)| !matches!(c,  'a'..='z' | 'A'..='Z' | '0'..='9' | '_'))
+        {
+            start += next + c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    name
+}
+
+/// Render debug elements into a standalone SVG image of the world area `bounds`, e.g. to look at
+/// a region generated in a headless test, or to diff two generations.
+///
+/// The SVG uses world coordinates directly, which, like in the example viewers, have `y` pointing down.
+/// [DebugContent::Chunk] draws an outline of `bounds`, as the elements don't know their chunk.
+pub fn to_svg(items: &[DebugContent], bounds: Bounds) -> String {
+    use std::fmt::Write as _;
+    let Bounds { min, max } = bounds;
+    let size = max - min;
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+        min.x, min.y, size.x, size.y, size.x, size.y
+    );
+    svg.push('\n');
+    svg.push_str(r#"<g fill="none" stroke="black" vector-effect="non-scaling-stroke">"#);
+    svg.push('\n');
+    for item in items {
+        match item {
+            DebugContent::Chunk => writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" stroke="lightgray"/>"#,
+                min.x, min.y, size.x, size.y
+            ),
+            DebugContent::Line(Line { start, end }) => writeln!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                start.x, start.y, end.x, end.y
+            ),
+            DebugContent::Circle { center, radius } => writeln!(
+                svg,
+                r#"<circle cx="{}" cy="{}" r="{radius}"/>"#,
+                center.x, center.y
+            ),
+            DebugContent::Text { pos, label } => {
+                write!(
+                    svg,
+                    r#"<text x="{}" y="{}" fill="black" stroke="none">"#,
+                    pos.x, pos.y
+                )
+                .and_then(|()| {
+                    // The first line starts at `pos`, the others below it.
+                    for (i, line) in label.lines().enumerate() {
+                        let dy = if i == 0 { "0" } else { "1.2em" };
+                        write!(
+                            svg,
+                            r#"<tspan x="{}" dy="{dy}">{}</tspan>"#,
+                            pos.x,
+                            escape(line)
+                        )?;
+                    }
+                    writeln!(svg, "</text>")
+                })
             }
-            if let Some((next, c)) = name[start..]
-                .char_indices()
-                .find(|&(_, c)| !matches!(c,  'a'..='z' | 'A'..='Z' | '0'..='9' | '_'))
-            {
-                start += next + c.len_utf8();
-            } else {
-                break;
+            DebugContent::Polygon { points, fill } => {
+                let fill = if *fill { r#" fill="black""# } else { "" };
+                let points: Vec<_> = points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+                writeln!(svg, r#"<polygon points="{}"{fill}/>"#, points.join(" "))
             }
         }
-        name
+        .unwrap();
     }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+#[test]
+fn svg() {
+    let bounds = Bounds {
+        min: Point2d::new(-10, 20),
+        max: Point2d::new(54, 84),
+    };
+    let svg = to_svg(
+        &[
+            DebugContent::Chunk,
+            DebugContent::Line(Point2d::new(-5, 25).to(Point2d::new(40, 70))),
+            DebugContent::Circle {
+                center: Point2d::new(3, 30),
+                radius: 2.5,
+            },
+            DebugContent::Text {
+                pos: Point2d::new(0, 50),
+                label: "a < b\nc".into(),
+            },
+            DebugContent::Polygon {
+                points: vec![
+                    Point2d::new(0, 20),
+                    Point2d::new(10, 30),
+                    Point2d::new(-5, 30),
+                ],
+                fill: true,
+            },
+        ],
+        bounds,
+    );
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-10 20 64 64""#));
+    assert!(svg.contains(r#"<rect x="-10" y="20" width="64" height="64" stroke="lightgray"/>"#));
+    assert!(svg.contains(r#"<line x1="-5" y1="25" x2="40" y2="70"/>"#));
+    assert!(svg.contains(r#"<circle cx="3" cy="30" r="2.5"/>"#));
+    assert!(svg.contains(
+        r#"<tspan x="0" dy="0">a &lt; b</tspan><tspan x="0" dy="1.2em">c</tspan></text>"#
+    ));
+    assert!(svg.contains(r#"<polygon points="0,20 10,30 -5,30" fill="black"/>"#));
+    assert!(svg.ends_with("</svg>\n"));
 }
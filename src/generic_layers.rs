@@ -1,11 +1,14 @@
 //! Various useful layer/chunk type combinations that you can reuse in many kind of games.
 
+use std::marker::PhantomData;
+
 use arrayvec::ArrayVec;
 use rand::prelude::*;
 
 use crate::{
-    Bounds, Chunk, ChunkExt as _, Seed,
+    Bounds, Chunk, ChunkExt as _, RollingGrid, Seed,
     debug::{Debug, DebugContent},
+    rng::ChunkRng,
     rolling_grid::GridPoint,
     vec2::{Num, Point2d},
 };
@@ -27,75 +30,144 @@ fn poisson_1(val: f32) -> u8 {
     }
 }
 
-#[derive(PartialEq, Debug, Clone)]
 /// A type of chunk that contains on average one point.
 /// You can specify a size in real world coordinates as well as
 /// a random number generator salt for picking different points
 /// even for the same chunk coordinates.
-pub struct UniformPoint<P, const SIZE: u8, const SALT: u64> {
+///
+/// The salt only distinguishes layers within one world. The [Seed] dependency is the
+/// runtime seed of the world, which is mixed with the salt and the chunk position
+/// (see [ChunkRng::new](crate::rng::ChunkRng::new)), so layers with the same salt still
+/// generate different points in worlds with different seeds.
+///
+/// The points are placed with a random number generator of type `R`, seeded via
+/// [rng_for_point_as]. Use e.g. a generator with a fixed algorithm to get the same
+/// points on all platforms, in contrast to the default [SmallRng].
+pub struct UniformPoint<P, const SIZE: u8, const SALT: u64, R = SmallRng> {
     /// The actual points. Can be up to 7, as a poisson distribution of one point
     /// per chunk has a negligible probability for more than 7 points.
+    /// A fixed number of points can be chosen via [Reducible::POINTS].
     pub points: ArrayVec<P, 7>,
+    rng: PhantomData<fn() -> R>,
 }
 
-impl<P, const SIZE: u8, const SALT: u64> Default for UniformPoint<P, SIZE, SALT> {
+impl<P, const SIZE: u8, const SALT: u64, R> Default for UniformPoint<P, SIZE, SALT, R> {
     fn default() -> Self {
         Self {
             points: Default::default(),
+            rng: PhantomData,
+        }
+    }
+}
+
+impl<P: Clone, const SIZE: u8, const SALT: u64, R> Clone for UniformPoint<P, SIZE, SALT, R> {
+    fn clone(&self) -> Self {
+        Self {
+            points: self.points.clone(),
+            rng: PhantomData,
         }
     }
 }
 
-impl<P: Reducible, const SIZE: u8, const SALT: u64> Chunk for UniformPoint<P, SIZE, SALT> {
+impl<P: PartialEq, const SIZE: u8, const SALT: u64, R> PartialEq
+    for UniformPoint<P, SIZE, SALT, R>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.points == other.points
+    }
+}
+
+impl<P: std::fmt::Debug, const SIZE: u8, const SALT: u64, R> std::fmt::Debug
+    for UniformPoint<P, SIZE, SALT, R>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UniformPoint")
+            .field("points", &self.points)
+            .finish()
+    }
+}
+
+impl<P: Reducible, const SIZE: u8, const SALT: u64, R: SeedableRng + RngCore + 'static> Chunk
+    for UniformPoint<P, SIZE, SALT, R>
+{
     type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
     type Dependencies = Seed;
 
     const SIZE: Point2d<u8> = Point2d::splat(SIZE);
 
-    fn compute(&seed: &Self::Dependencies, index: GridPoint<Self>) -> Self {
-        let points = generate_points::<SALT, Self>(index, seed);
+    fn compute_in_bounds(
+        &seed: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        const {
+            assert!(
+                matches!(P::POINTS, None | Some(0..=7)),
+                "at most 7 points per chunk are supported"
+            )
+        };
+        let rng = rng_for_point_as::<R, SALT, _>(index, seed);
         Self {
-            points: points.map(P::from).collect(),
+            points: generate_points(index, rng, P::POINTS)
+                .map(P::from)
+                .collect(),
+            rng: PhantomData,
         }
     }
-
-    fn clear(_seed: &Self::Dependencies, _index: GridPoint<Self>) {
-        // Nothing to do, we do not have dependencies
-    }
 }
 
-impl<P: Reducible, const SIZE: u8, const SALT: u64> Debug for UniformPoint<P, SIZE, SALT> {
+impl<P: Reducible, const SIZE: u8, const SALT: u64, R: SeedableRng + RngCore + 'static> Debug
+    for UniformPoint<P, SIZE, SALT, R>
+{
     fn debug(&self, bounds: Bounds) -> Vec<DebugContent> {
         self.points.iter().flat_map(|p| p.debug(bounds)).collect()
     }
 }
 
-fn generate_points<const SALT: u64, C: Chunk + 'static>(
+fn generate_points<C: Chunk + 'static>(
     index: GridPoint<C>,
-    seed: Seed,
+    mut rng: impl RngCore,
+    points: Option<u8>,
 ) -> impl Iterator<Item = Point2d> {
-    let chunk_bounds = C::bounds(index);
-    let mut rng = rng_for_point::<SALT, _>(index, seed);
-    let n = poisson_1(rng.random_range(0.0..=1.0)).into();
-    std::iter::from_fn(move || Some(chunk_bounds.sample(&mut rng))).take(n)
+    let n = match points {
+        Some(n) => n,
+        None => poisson_1(rng.random_range(0.0..=1.0)),
+    }
+    .into();
+    std::iter::from_fn(move || Some(C::sample_point(index, &mut rng))).take(n)
 }
 
 /// Create a random number generator seeded with a specific point.
+/// Use [ChunkRng] if you need multiple independent random number generators.
 pub fn rng_for_point<const SALT: u64, T: Num>(index: Point2d<T>, seed: Seed) -> SmallRng {
-    let x = SmallRng::seed_from_u64(index.x.as_u64());
-    let y = SmallRng::seed_from_u64(index.y.as_u64());
-    let salt = SmallRng::seed_from_u64(SALT);
-    let seeded = SmallRng::seed_from_u64(seed.0);
-    let mut seed = <SmallRng as SeedableRng>::Seed::default();
-    for mut rng in [x, y, salt, seeded] {
-        let mut tmp = <SmallRng as SeedableRng>::Seed::default();
-        rng.fill_bytes(&mut tmp);
-        for (seed, tmp) in seed.iter_mut().zip(tmp.iter()) {
-            *seed ^= *tmp;
-        }
-    }
-    SmallRng::from_seed(seed)
+    rng_for_point_as::<SmallRng, SALT, _>(index, seed)
+}
+
+/// Same as [rng_for_point], but for any type of random number generator,
+/// see [ChunkRng::base_as].
+pub fn rng_for_point_as<R: SeedableRng, const SALT: u64, T: Num>(
+    index: Point2d<T>,
+    seed: Seed,
+) -> R {
+    ChunkRng::new::<SALT, _>(index, seed).base_as()
 }
 
+mod configured;
+pub use configured::*;
+mod lod;
+pub use lod::*;
+mod merged_points;
+pub use merged_points::*;
+mod neighborhood_graph;
+pub use neighborhood_graph::*;
+mod poisson_points;
+pub use poisson_points::*;
 mod reduced_points;
 pub use reduced_points::*;
+mod summarized;
+pub use summarized::*;
+mod value_noise;
+pub use value_noise::*;
+mod weighted_points;
+pub use weighted_points::*;
@@ -0,0 +1,82 @@
+use arrayvec::ArrayVec;
+
+use crate::{
+    Bounds, Chunk, Dependencies, RollingGrid, Seed,
+    debug::{Debug, DebugContent, DynLayer},
+    rng::ChunkRng,
+    rolling_grid::GridPoint,
+    vec2::Point2d,
+};
+
+use super::{Reducible, generate_points};
+
+/// The settings of a [ConfiguredUniformPoint] layer that are chosen at runtime,
+/// e.g. loaded from a config file at startup (via the `serde` feature).
+///
+/// The chunk size and the size of the cache can't be configured at runtime, as all
+/// index computations and the [RollingGrid] depend on them at compile time.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LayerConfig {
+    /// The seed of the world.
+    pub seed: Seed,
+    /// Distinguishes layers that generate the same kind of points, like the `SALT` of [UniformPoint](super::UniformPoint).
+    pub salt: u64,
+    /// How many points to generate per chunk. `None` uses [Reducible::POINTS].
+    pub points: Option<u8>,
+}
+
+impl Dependencies for LayerConfig {
+    const HAS_LAYERS: bool = false;
+
+    fn debug(&self) -> Vec<&dyn DynLayer> {
+        vec![]
+    }
+}
+
+/// Same as [UniformPoint](super::UniformPoint), but with the salt and number of points taken
+/// from a [LayerConfig] at runtime instead of from const generics.
+/// Generates the same points as a [UniformPoint](super::UniformPoint) with the same settings.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ConfiguredUniformPoint<P, const SIZE: u8> {
+    /// The generated points.
+    pub points: ArrayVec<P, 7>,
+}
+
+impl<P, const SIZE: u8> Default for ConfiguredUniformPoint<P, SIZE> {
+    fn default() -> Self {
+        Self {
+            points: Default::default(),
+        }
+    }
+}
+
+impl<P: Reducible, const SIZE: u8> Chunk for ConfiguredUniformPoint<P, SIZE> {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = LayerConfig;
+
+    const SIZE: Point2d<u8> = Point2d::splat(SIZE);
+
+    fn compute_in_bounds(
+        config: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        let points = config.points.or(P::POINTS);
+        assert!(
+            matches!(points, None | Some(0..=7)),
+            "at most 7 points per chunk are supported, not {points:?}"
+        );
+        let rng = ChunkRng::with_salt(index, config.salt, config.seed).base();
+        Self {
+            points: generate_points(index, rng, points).map(P::from).collect(),
+        }
+    }
+}
+
+impl<P: Reducible, const SIZE: u8> Debug for ConfiguredUniformPoint<P, SIZE> {
+    fn debug(&self, bounds: Bounds) -> Vec<DebugContent> {
+        self.points.iter().flat_map(|p| p.debug(bounds)).collect()
+    }
+}
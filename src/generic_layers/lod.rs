@@ -0,0 +1,94 @@
+use crate::{
+    Chunk, ChunkExt as _, Layer, RollingGrid,
+    debug::Debug,
+    rolling_grid::GridPoint,
+    vec2::{Bounds, Point2d},
+};
+
+/// Chunks that can be approximated at coarser levels of detail, e.g. for zoomed out views
+/// that would need lots of full detail chunks but can't show their details anyway.
+///
+/// A [Coarse] chunk of level `L` covers exactly `2^L * 2^L` chunks of this layer, so the borders
+/// between levels are always chunk borders. Where two levels meet, nothing is blended, each side
+/// shows its own content. For that to be seamless, [LodChunk::compute_coarse] should only produce
+/// features that the full chunks have, too, at the same positions: e.g. by deriving them from
+/// world positions and the same dependencies, and skipping the small ones.
+pub trait LodChunk: Chunk + Debug {
+    /// The approximated content of a [Coarse] chunk.
+    type Coarse: Clone + Default + 'static;
+
+    /// The coarsest level [Coarse] chunks of this layer can have.
+    const MAX_LEVEL: u8;
+
+    /// Approximate the area `bounds`, which covers `2^level * 2^level` chunks of this layer.
+    /// Usually uses the dependencies of `fine` (via [Deref](std::ops::Deref)) directly instead of its chunks,
+    /// which would be just as expensive as the full detail.
+    fn compute_coarse(fine: &Layer<Self>, bounds: Bounds, level: u8) -> Self::Coarse;
+
+    /// Clear everything [LodChunk::compute_coarse] loaded for `bounds`.
+    /// Clears the full chunks touching `bounds` and what they were computed from by default.
+    fn clear_coarse(fine: &Layer<Self>, bounds: Bounds, level: u8) {
+        let _ = level;
+        fine.clear(bounds);
+    }
+
+    /// The level of detail to show the area `view` (in world coordinates) at: full detail (`0`)
+    /// if it is at most 8 chunks wide and high, and one level coarser for each doubling of that,
+    /// up to [LodChunk::MAX_LEVEL].
+    fn lod_for(view: Bounds) -> u8 {
+        let size = view.max - view.min;
+        let chunks = (size.x >> Self::SIZE.x).max(size.y >> Self::SIZE.y);
+        let level = if chunks <= 8 {
+            0
+        } else {
+            ((chunks - 1) / 8).ilog2() + 1
+        };
+        u8::try_from(level).map_or(Self::MAX_LEVEL, |level| level.min(Self::MAX_LEVEL))
+    }
+}
+
+/// The approximation of `2^LEVEL * 2^LEVEL` chunks of `C`, see [LodChunk].
+/// Use [GridPoint::covering_indices] to get the indices of those chunks.
+pub struct Coarse<C: LodChunk, const LEVEL: u8>(pub C::Coarse);
+
+impl<C: LodChunk, const LEVEL: u8> Clone for Coarse<C, LEVEL> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<C: LodChunk, const LEVEL: u8> Default for Coarse<C, LEVEL> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<C: LodChunk, const LEVEL: u8> Chunk for Coarse<C, LEVEL> {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Layer<C>;
+
+    const SIZE: Point2d<u8> = Point2d::new(C::SIZE.x + LEVEL, C::SIZE.y + LEVEL);
+    const COORD_MODE: crate::vec2::CoordMode = C::COORD_MODE;
+    const ORIGIN: Point2d = C::ORIGIN;
+
+    fn compute_in_bounds(
+        fine: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        const {
+            assert!(
+                LEVEL > 0 && LEVEL <= C::MAX_LEVEL,
+                "LEVEL must be within 1..=LodChunk::MAX_LEVEL"
+            )
+        };
+        Self(C::compute_coarse(fine, bounds, LEVEL))
+    }
+
+    fn clear(fine: &Self::Dependencies, index: GridPoint<Self>) {
+        C::clear_coarse(fine, Self::bounds(index), LEVEL)
+    }
+}
+
+impl<C: LodChunk, const LEVEL: u8> Debug for Coarse<C, LEVEL> {}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use arrayvec::ArrayVec;
+
+use crate::{
+    Chunk, ChunkExt as _, Dependencies, Layer, RollingGrid,
+    debug::{Debug, DebugContent, DynLayer},
+    rolling_grid::GridPoint,
+    vec2::{Bounds, Point2d},
+};
+
+use super::{ReducedUniformPoint, Reducible};
+
+/// Combines two [ReducedUniformPoint] layers of different types, removing the things
+/// that are too close to a thing of the other type.
+///
+/// Of two things that are too close to each other, the one with the higher [Reducible::priority]
+/// is kept. For equal priorities the one with the higher position is kept, and for equal positions the
+/// thing of type `A`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct MergedReducedPoints<A, B, const SIZE: u8, const SALT_A: u64, const SALT_B: u64> {
+    /// The things of type `A` that are not too close to a more important `B`.
+    pub first: ArrayVec<A, 7>,
+    /// The things of type `B` that are not too close to a more important `A`.
+    pub second: ArrayVec<B, 7>,
+}
+
+impl<A, B, const SIZE: u8, const SALT_A: u64, const SALT_B: u64> Default
+    for MergedReducedPoints<A, B, SIZE, SALT_A, SALT_B>
+{
+    fn default() -> Self {
+        Self {
+            first: Default::default(),
+            second: Default::default(),
+        }
+    }
+}
+
+/// The dependencies of [MergedReducedPoints].
+pub struct MergedPointsDeps<
+    A: Reducible,
+    B: Reducible,
+    const SIZE: u8,
+    const SALT_A: u64,
+    const SALT_B: u64,
+> {
+    /// The things of type `A`.
+    pub first: Layer<ReducedUniformPoint<A, SIZE, SALT_A>>,
+    /// The things of type `B`.
+    pub second: Layer<ReducedUniformPoint<B, SIZE, SALT_B>>,
+}
+
+impl<A: Reducible, B: Reducible, const SIZE: u8, const SALT_A: u64, const SALT_B: u64> Default
+    for MergedPointsDeps<A, B, SIZE, SALT_A, SALT_B>
+{
+    fn default() -> Self {
+        Self {
+            first: Default::default(),
+            second: Default::default(),
+        }
+    }
+}
+
+impl<A: Reducible, B: Reducible, const SIZE: u8, const SALT_A: u64, const SALT_B: u64> Dependencies
+    for MergedPointsDeps<A, B, SIZE, SALT_A, SALT_B>
+{
+    fn debug(&self) -> Vec<&dyn DynLayer> {
+        vec![&self.first, &self.second]
+    }
+
+    fn version(&self) -> u64 {
+        crate::combine_versions([self.first.version(), self.second.version()])
+    }
+}
+
+/// Keep the things of `points` that are not too close to a more important thing in `others`.
+fn remove_conflicts<P: Reducible, Q: Reducible, C: Chunk>(
+    points: impl IntoIterator<Item = P>,
+    others: &Layer<C>,
+    others_points: impl Fn(C) -> ArrayVec<Q, 7>,
+    wins_ties: bool,
+) -> ArrayVec<P, 7> {
+    let mut kept = ArrayVec::new();
+    'points: for p in points {
+        let range =
+            Bounds::point(p.position()).pad(Point2d::splat(p.radius() + Q::RADIUS_RANGE.end));
+        for chunk in others.get_range(range) {
+            for other in others_points(chunk) {
+                let too_close =
+                    other.position().manhattan_dist(p.position()) < p.radius() + other.radius();
+                let priority =
+                    (other.priority(), other.position()).cmp(&(p.priority(), p.position()));
+                if too_close && (priority.is_gt() || (priority.is_eq() && !wins_ties)) {
+                    continue 'points;
+                }
+            }
+        }
+        kept.push(p);
+    }
+    kept
+}
+
+impl<A: Reducible, B: Reducible, const SIZE: u8, const SALT_A: u64, const SALT_B: u64> Chunk
+    for MergedReducedPoints<A, B, SIZE, SALT_A, SALT_B>
+{
+    type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = MergedPointsDeps<A, B, SIZE, SALT_A, SALT_B>;
+    const SIZE: Point2d<u8> = Point2d::splat(SIZE);
+
+    fn compute_in_bounds(
+        MergedPointsDeps { first, second }: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        let first_points = first.get(index.same_index()).points;
+        let second_points = second.get(index.same_index()).points;
+        Self {
+            first: remove_conflicts(first_points, second, |c| c.points, true),
+            second: remove_conflicts(second_points, first, |c| c.points, false),
+        }
+    }
+
+    fn clear(MergedPointsDeps { first, second }: &Self::Dependencies, index: GridPoint<Self>) {
+        let bounds = Self::bounds(index);
+        first.clear(bounds.pad(Point2d::splat(A::RADIUS_RANGE.end + B::RADIUS_RANGE.end)));
+        second.clear(bounds.pad(Point2d::splat(A::RADIUS_RANGE.end + B::RADIUS_RANGE.end)));
+    }
+}
+
+impl<A: Reducible, B: Reducible, const SIZE: u8, const SALT_A: u64, const SALT_B: u64> Debug
+    for MergedReducedPoints<A, B, SIZE, SALT_A, SALT_B>
+{
+    fn debug(&self, bounds: Bounds) -> Vec<DebugContent> {
+        self.first
+            .iter()
+            .flat_map(|p| p.debug(bounds))
+            .chain(self.second.iter().flat_map(|p| p.debug(bounds)))
+            .collect()
+    }
+}
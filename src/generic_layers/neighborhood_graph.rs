@@ -0,0 +1,227 @@
+use std::{borrow::Borrow, marker::PhantomData, sync::Arc};
+
+use rand::prelude::*;
+
+use crate::{
+    Chunk, ChunkExt as _, Layer, RollingGrid,
+    debug::{Debug, DebugContent},
+    rolling_grid::GridPoint,
+    vec2::{Bounds, Line, Point2d},
+};
+
+use super::{ReducedUniformPoint, Reducible};
+
+/// Chunks containing points, e.g. locations that [NeighborhoodGraph] connects.
+pub trait PointChunk: Chunk {
+    /// The positions of the points of this chunk, in a fixed order.
+    fn positions(&self) -> impl Iterator<Item = Point2d> + '_;
+}
+
+impl<P: Reducible, const SIZE: u8, const SALT: u64, R: SeedableRng + RngCore + 'static> PointChunk
+    for ReducedUniformPoint<P, SIZE, SALT, R>
+{
+    fn positions(&self) -> impl Iterator<Item = Point2d> + '_ {
+        self.points.iter().map(P::position)
+    }
+}
+
+/// How [relative_neighborhood_graph] measures distances. Distances are only compared with
+/// each other, so they don't need to be actual lengths (e.g. squared distances work, too),
+/// but they need to be the same in both directions.
+pub trait Metric {
+    /// The distance between `a` and `b`.
+    fn dist(&self, a: Point2d, b: Point2d) -> i64;
+
+    /// A lower bound for the distance between any two points whose x coordinates
+    /// differ by `delta`, to skip computing distances that are too large anyway.
+    fn axis_dist(&self, delta: i64) -> i64 {
+        let _ = delta;
+        0
+    }
+}
+
+/// Straight line distances, via [Point2d::dist_squared].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Euclidean;
+
+impl Metric for Euclidean {
+    fn dist(&self, a: Point2d, b: Point2d) -> i64 {
+        a.dist_squared(b)
+    }
+
+    fn axis_dist(&self, delta: i64) -> i64 {
+        delta * delta
+    }
+}
+
+/// Distances along the axes, via [Point2d::manhattan_dist].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Manhattan;
+
+impl Metric for Manhattan {
+    fn dist(&self, a: Point2d, b: Point2d) -> i64 {
+        a.manhattan_dist(b)
+    }
+
+    fn axis_dist(&self, delta: i64) -> i64 {
+        delta.abs()
+    }
+}
+
+/// Compute the edges of the [relative neighborhood graph](https://en.m.wikipedia.org/wiki/Relative_neighborhood_graph)
+/// starting at the points of the `center` chunk.
+///
+/// `chunks` are the points of the chunks around `center` (usually its
+/// [moore neighborhood](crate::ChunkExt::moore_neighborhood)), together with their index.
+/// Calls `mk` for every edge and returns the results, and calls `rejected(a, b, veto)` for every
+/// candidate edge from `a` to `b` that is not part of the graph because `veto` is closer to both of them.
+///
+/// Only edges to points after the point of the center chunk in `chunks` are emitted, so if every chunk
+/// computes its edges with the same order of neighbors, each edge is emitted by exactly one of them.
+/// Edges are only correct if the points are dense enough that the points closer to both
+/// ends of an edge are within `chunks`.
+pub fn relative_neighborhood_graph<I: PartialEq, T: Clone, U>(
+    metric: impl Metric,
+    chunks: impl IntoIterator<Item = (I, impl Borrow<[T]>)>,
+    center: I,
+    get_point: impl Fn(&T) -> Point2d,
+    mk: impl Fn(&T, &T) -> U,
+    mut rejected: impl FnMut(&T, &T, &T),
+) -> Vec<U> {
+    let mut edges = vec![];
+    let mut points = vec![];
+    let mut start = 0;
+    let mut n = 0;
+    for (index, chunk) in chunks {
+        let chunk = chunk.borrow();
+        if index == center {
+            start = points.len();
+            n = chunk.len();
+        }
+        points.extend(chunk.iter().cloned());
+    }
+    let positions: Vec<Point2d> = points.iter().map(&get_point).collect();
+    let len = positions.len();
+    // Each distance is needed by many candidate edges, so compute it at most once.
+    let mut cache = vec![None; len * len];
+    let mut dist = |i: usize, j: usize| {
+        *cache[i.min(j) * len + i.max(j)]
+            .get_or_insert_with(|| metric.dist(positions[i], positions[j]))
+    };
+    // We only care about the edges starting from the center chunk, as the others are not necessarily correct,
+    // or will be computed by the other chunks.
+    // The others may connect the outer edges of the current range and thus connect points that
+    // don't satisfy the algorithm.
+    // It's a brute force implementation, but I think that is faster than going through
+    // a Delaunay triangulation first, as instead of (3*9)^3 = 19683 inner loop iterations we have only
+    // 3 * (2 + 1 + 3*4) * 3*9 = 1215
+    // FIXME: figure out how to cache distances across chunks (along with removing them from the cache
+    // when they aren't needed anymore) as the neighboring chunks will be redoing the same distance computations.
+    for i in start..start + n {
+        let a = positions[i];
+        for j in i + 1..len {
+            let b = positions[j];
+            let ab = dist(i, j);
+            let veto = (0..len).find(|&k| {
+                let c = positions[k];
+                if a == c || b == c {
+                    return false;
+                }
+                if metric.axis_dist(a.x - c.x) > ab || metric.axis_dist(c.x - b.x) > ab {
+                    return false;
+                }
+                dist(i, k) <= ab && dist(k, j) <= ab
+            });
+            match veto {
+                None => edges.push(mk(&points[i], &points[j])),
+                Some(k) => rejected(&points[i], &points[j], &points[k]),
+            }
+        }
+    }
+    edges
+}
+
+/// The edges of the [relative neighborhood graph](relative_neighborhood_graph) of the points
+/// of a [PointChunk] layer, e.g. roads connecting intersections.
+///
+/// Each chunk contains the edges starting at its own points, so every edge is in exactly one chunk.
+/// Edges are only found if their ends are at most one chunk apart, and the points
+/// need to be dense enough that no edge is longer than a chunk.
+pub struct NeighborhoodGraph<P, M = Euclidean> {
+    /// The edges starting at the points of this chunk.
+    pub edges: Arc<[Line]>,
+    marker: PhantomData<fn() -> (P, M)>,
+}
+
+impl<P, M> Default for NeighborhoodGraph<P, M> {
+    fn default() -> Self {
+        Self {
+            edges: Arc::new([]),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P, M> Clone for NeighborhoodGraph<P, M> {
+    fn clone(&self) -> Self {
+        Self {
+            edges: self.edges.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P, M> PartialEq for NeighborhoodGraph<P, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.edges == other.edges
+    }
+}
+
+impl<P, M> std::fmt::Debug for NeighborhoodGraph<P, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NeighborhoodGraph")
+            .field("edges", &self.edges)
+            .finish()
+    }
+}
+
+impl<P: PointChunk + Debug, M: Metric + Default + 'static> Chunk for NeighborhoodGraph<P, M> {
+    type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Layer<P>;
+    const SIZE: Point2d<u8> = P::SIZE;
+
+    fn compute_in_bounds(
+        points: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        let center = index.same_index::<P>();
+        let chunks = P::moore_neighborhood(center)
+            .into_iter()
+            .flatten()
+            .map(|index| (index, points.get(index).positions().collect::<Vec<_>>()));
+        let edges = relative_neighborhood_graph(
+            M::default(),
+            chunks,
+            center,
+            |&p| p,
+            |&a, &b| a.to(b),
+            |_, _, _| {},
+        );
+        Self {
+            edges: edges.into(),
+            marker: PhantomData,
+        }
+    }
+
+    fn clear(points: &Self::Dependencies, index: GridPoint<Self>) {
+        points.clear(P::vision_range(P::bounds(index.same_index())));
+    }
+}
+
+impl<P, M> Debug for NeighborhoodGraph<P, M> {
+    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
+        self.edges.iter().copied().map(DebugContent::from).collect()
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    f64::consts::{SQRT_2, TAU},
+    sync::Arc,
+};
+
+use rand::prelude::*;
+
+use crate::{
+    Chunk, ChunkExt as _, Layer, RollingGrid, Seed,
+    debug::{Debug, DebugContent},
+    rolling_grid::GridPoint,
+    vec2::{Bounds, Point2d},
+};
+
+use super::rng_for_point;
+
+/// How many candidates are tried around each point before giving up on it (`k` in Bridson's algorithm).
+const ATTEMPTS: usize = 30;
+
+#[derive(PartialEq, Debug, Clone, Default)]
+/// Blue noise points of a single chunk that are at least `RADIUS` apart from each other,
+/// without looking at neighboring chunks. [PoissonPoint] removes the conflicts between chunks.
+pub struct PoissonCandidates<const SIZE: u8, const SALT: u64, const RADIUS: i64> {
+    /// The points together with a random priority for resolving conflicts with other chunks.
+    pub points: Arc<[(Point2d, u64)]>,
+}
+
+impl<const SIZE: u8, const SALT: u64, const RADIUS: i64> Chunk
+    for PoissonCandidates<SIZE, SALT, RADIUS>
+{
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Seed;
+
+    const SIZE: Point2d<u8> = Point2d::splat(SIZE);
+
+    fn compute_in_bounds(
+        &seed: &Self::Dependencies,
+        index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        const { assert!(RADIUS > 0, "RADIUS must be positive") };
+        let mut rng = rng_for_point::<SALT, _>(index, seed);
+        let points = bridson(bounds, RADIUS, &mut rng);
+        Self {
+            points: points.into_iter().map(|p| (p, rng.random())).collect(),
+        }
+    }
+}
+
+/// Fill the half-open `bounds` with points that are at least `radius` apart,
+/// see <https://www.cs.ubc.ca/~rbridson/docs/bridson-siggraph07-poissondisk.pdf>.
+fn bridson(bounds: Bounds, radius: i64, rng: &mut impl Rng) -> Vec<Point2d> {
+    // Small enough that no two points can be in the same cell.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "smaller than the radius, which is an i64"
+    )]
+    let cell = ((radius as f64 / SQRT_2) as i64).max(1);
+    let cells = (bounds.max - bounds.min - Point2d::splat(1)) / cell + Point2d::splat(1);
+    let cell_index = |cell: Point2d| usize::try_from(cell.x + cell.y * cells.x).unwrap();
+    let mut grid: Vec<Option<usize>> = vec![None; cell_index(cells)];
+    // How many cells away points can still be closer than `radius`.
+    let reach = radius / cell + 1;
+
+    let mut points = vec![];
+    let mut active = vec![];
+    let insert = |p: Point2d,
+                  grid: &mut Vec<Option<usize>>,
+                  points: &mut Vec<Point2d>,
+                  active: &mut Vec<usize>| {
+        grid[cell_index((p - bounds.min) / cell)] = Some(points.len());
+        active.push(points.len());
+        points.push(p);
+    };
+    insert(bounds.sample(rng), &mut grid, &mut points, &mut active);
+    while !active.is_empty() {
+        let i = rng.random_range(0..active.len());
+        let center = points[active[i]];
+        let candidate = (0..ATTEMPTS)
+            .map(|_| {
+                let angle = rng.random_range(0.0..TAU);
+                let dist = rng.random_range(radius as f64..2.0 * radius as f64);
+                #[expect(clippy::cast_possible_truncation, reason = "at most twice the radius")]
+                let offset = Point2d::new(
+                    (angle.cos() * dist).round() as i64,
+                    (angle.sin() * dist).round() as i64,
+                );
+                center + offset
+            })
+            .find(|&p| {
+                if !(bounds.min.x..bounds.max.x).contains(&p.x)
+                    || !(bounds.min.y..bounds.max.y).contains(&p.y)
+                {
+                    return false;
+                }
+                let cell = (p - bounds.min) / cell;
+                let near = Bounds::point(cell).pad(Point2d::splat(reach));
+                let near = Bounds {
+                    min: Point2d::new(near.min.x.max(0), near.min.y.max(0)),
+                    max: Point2d::new(near.max.x.min(cells.x), near.max.y.min(cells.y)),
+                };
+                near.iter()
+                    .filter_map(|cell| grid[cell_index(cell)])
+                    .all(|other| points[other].dist_squared(p) >= radius * radius)
+            });
+        match candidate {
+            Some(p) => insert(p, &mut grid, &mut points, &mut active),
+            None => {
+                active.swap_remove(i);
+            }
+        }
+    }
+    points
+}
+
+impl<const SIZE: u8, const SALT: u64, const RADIUS: i64> Debug
+    for PoissonCandidates<SIZE, SALT, RADIUS>
+{
+    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
+        self.points
+            .iter()
+            .map(|&(center, _)| DebugContent::Circle {
+                center,
+                radius: RADIUS as f32,
+            })
+            .collect()
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+/// Blue noise points that are at least `RADIUS` apart from each other, also across chunk borders.
+///
+/// In contrast to [ReducedUniformPoint](super::ReducedUniformPoint), the points of a chunk are
+/// already evenly spread out when they are generated. Only points close to the chunk borders
+/// can conflict with the points of neighboring chunks, in which case the one with the
+/// lower random priority is removed.
+pub struct PoissonPoint<const SIZE: u8, const SALT: u64, const RADIUS: i64> {
+    /// The points remaining after removing the ones conflicting with neighboring chunks.
+    pub points: Arc<[Point2d]>,
+}
+
+impl<const SIZE: u8, const SALT: u64, const RADIUS: i64> Chunk
+    for PoissonPoint<SIZE, SALT, RADIUS>
+{
+    type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Layer<PoissonCandidates<SIZE, SALT, RADIUS>>;
+    const SIZE: Point2d<u8> = Point2d::splat(SIZE);
+
+    fn compute_in_bounds(
+        candidates: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        let index = index.same_index();
+        let points = candidates
+            .get(index)
+            .points
+            .iter()
+            .filter(|&&(p, priority)| {
+                let mut suppressed = false;
+                let range = Bounds::point(p).pad(Point2d::splat(RADIUS));
+                candidates.with_range(range, |other_index, other| {
+                    // Points of the same chunk are already far enough apart.
+                    if other_index == index {
+                        return;
+                    }
+                    suppressed |= other.points.iter().any(|&(other, other_priority)| {
+                        other.dist_squared(p) < RADIUS * RADIUS
+                            && (other_priority, other) > (priority, p)
+                    });
+                });
+                !suppressed
+            })
+            .map(|&(p, _)| p)
+            .collect();
+        Self { points }
+    }
+
+    fn clear(candidates: &Self::Dependencies, index: GridPoint<Self>) {
+        candidates.clear(Self::bounds(index));
+    }
+}
+
+impl<const SIZE: u8, const SALT: u64, const RADIUS: i64> Debug
+    for PoissonPoint<SIZE, SALT, RADIUS>
+{
+    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
+        self.points
+            .iter()
+            .map(|&center| DebugContent::Circle { center, radius: 1. })
+            .collect()
+    }
+}
@@ -1,27 +1,58 @@
-use std::{ops::Range, sync::Arc};
+use std::{marker::PhantomData, ops::Range, sync::Arc};
 
 use arrayvec::ArrayVec;
+use rand::prelude::*;
 
 use crate::{
-    Chunk, ChunkExt as _, Layer,
+    Chunk, ChunkExt as _, Layer, RollingGrid,
     debug::{Debug, DebugContent},
-    rolling_grid::GridPoint,
+    rolling_grid::{GridIndex, GridPoint},
     vec2::{Bounds, Point2d},
 };
 
 use super::UniformPoint;
 
 /// Represents point like types that do not want to be close to other types.
-/// The larger of two objects is kept if they are too close to each other.
+/// By default the larger of two objects is kept if they are too close to each other.
 /// If both objects have the same radius, the one with the higher X coordinate is kept (or higher Y if X is also the same).
+/// Override [Reducible::wins_over] for different rules.
 pub trait Reducible: From<Point2d> + PartialEq + Clone + Sized + 'static {
     /// The range of radii that `radius` can return.
     const RADIUS_RANGE: Range<i64>;
 
+    /// How many points [UniformPoint] generates per chunk. By default (`None`) this is random,
+    /// with one point per chunk on average.
+    /// Can be at most 7, the capacity of [UniformPoint::points].
+    const POINTS: Option<u8> = None;
+
     /// The radius around the thing to be kept free from other things.
     fn radius(&self) -> i64;
     /// Center position of the circle to keep free of other things.
     fn position(&self) -> Point2d;
+    /// The largest radius of other things that can cause this one to get removed.
+    /// Together with [Reducible::radius] it determines how far around this thing
+    /// the reduction needs to look for other things.
+    /// Defaults to the end of [Reducible::RADIUS_RANGE], override it if you know that
+    /// only a subset of things can be close enough to this one to remove it.
+    fn conflict_radius(&self) -> i64 {
+        Self::RADIUS_RANGE.end
+    }
+    /// Importance of this thing when it is too close to a thing of a different type,
+    /// e.g. in [MergedReducedPoints](super::MergedReducedPoints). Higher priorities are kept.
+    /// Defaults to the radius, like [Reducible::wins_over].
+    fn priority(&self) -> i64 {
+        self.radius()
+    }
+    /// Whether this thing is kept and `other` removed if they are too close to each other.
+    /// Must be a strict total order, i.e. exactly one of `a.wins_over(b)` and `b.wins_over(a)`
+    /// is true for any two distinct things.
+    /// Defaults to preferring the larger radius, then the higher x, then the higher y coordinate.
+    fn wins_over(&self, other: &Self) -> bool {
+        self.radius()
+            .cmp(&other.radius())
+            .then_with(|| self.position().cmp(&other.position()))
+            .is_gt()
+    }
     /// Debug representation. Usually contains just a single thing, the item itself,
     /// but can be overriden to emit addition information.
     fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
@@ -32,63 +63,159 @@ pub trait Reducible: From<Point2d> + PartialEq + Clone + Sized + 'static {
     }
 }
 
-#[derive(PartialEq, Debug, Clone)]
 /// Removes locations that are too close to others.
-pub struct ReducedUniformPoint<P, const SIZE: u8, const SALT: u64> {
+///
+/// `R` is the random number generator used by the [UniformPoint] layer generating the locations.
+pub struct ReducedUniformPoint<P, const SIZE: u8, const SALT: u64, R = SmallRng> {
     /// The points remaining after removing ones that are too close to others.
     pub points: ArrayVec<P, 7>,
+    rng: PhantomData<fn() -> R>,
 }
 
-impl<P, const SIZE: u8, const SALT: u64> Default for ReducedUniformPoint<P, SIZE, SALT> {
+impl<P, const SIZE: u8, const SALT: u64, R> Default for ReducedUniformPoint<P, SIZE, SALT, R> {
     fn default() -> Self {
         Self {
             points: Default::default(),
+            rng: PhantomData,
         }
     }
 }
 
-impl<P: Reducible, const SIZE: u8, const SALT: u64> Chunk for ReducedUniformPoint<P, SIZE, SALT> {
+impl<P: Clone, const SIZE: u8, const SALT: u64, R> Clone for ReducedUniformPoint<P, SIZE, SALT, R> {
+    fn clone(&self) -> Self {
+        Self {
+            points: self.points.clone(),
+            rng: PhantomData,
+        }
+    }
+}
+
+impl<P: PartialEq, const SIZE: u8, const SALT: u64, R> PartialEq
+    for ReducedUniformPoint<P, SIZE, SALT, R>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.points == other.points
+    }
+}
+
+impl<P: std::fmt::Debug, const SIZE: u8, const SALT: u64, R> std::fmt::Debug
+    for ReducedUniformPoint<P, SIZE, SALT, R>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReducedUniformPoint")
+            .field("points", &self.points)
+            .finish()
+    }
+}
+
+impl<P: Reducible, const SIZE: u8, const SALT: u64, R: SeedableRng + RngCore + 'static> Chunk
+    for ReducedUniformPoint<P, SIZE, SALT, R>
+{
     type LayerStore<T> = Arc<T>;
-    type Dependencies = Layer<UniformPoint<P, SIZE, SALT>>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Layer<UniformPoint<P, SIZE, SALT, R>>;
     const SIZE: Point2d<u8> = Point2d::splat(SIZE);
 
-    fn compute(raw_points: &Self::Dependencies, index: GridPoint<Self>) -> Self {
+    fn compute_in_bounds(
+        raw_points: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
         let mut points = ArrayVec::new();
-        'points: for p in raw_points.get(index.into_same_chunk_size()).points {
-            for other in raw_points.get_range(
-                Bounds::point(p.position()).pad(Point2d::splat(p.radius() + P::RADIUS_RANGE.end)),
-            ) {
-                for other in other.points {
-                    if other == p {
-                        continue;
-                    }
+        for p in raw_points.get(index.same_index()).points {
+            let range =
+                Bounds::point(p.position()).pad(Point2d::splat(p.radius() + p.conflict_radius()));
+            let mut suppressed = false;
+            // `bounds_to_grid` includes every chunk containing a point of the padded area,
+            // even if the area ends just past a chunk border.
+            raw_points.with_range(range, |_, other| {
+                // skip current point if another point's center is within our radius and we have lower priority
+                suppressed |= other.points.iter().any(|other| {
+                    let conflict = other != &p
+                        && other.position().manhattan_dist(p.position())
+                            < p.radius() + other.radius();
+                    // Conflicting things further away than the scanned area would be missed.
+                    debug_assert!(
+                        !conflict || other.radius() <= p.conflict_radius(),
+                        "radius {} is larger than the conflict radius {}",
+                        other.radius(),
+                        p.conflict_radius(),
+                    );
+                    conflict && other.wins_over(&p)
+                });
+            });
+            if !suppressed {
+                points.push(p);
+            }
+        }
+        ReducedUniformPoint {
+            points,
+            rng: PhantomData,
+        }
+    }
 
-                    // prefer to delete lower radius, then lower x, then lower y
-                    let lower_priority = p
-                        .radius()
-                        .cmp(&other.radius())
-                        .then_with(|| p.position().cmp(&other.position()))
-                        .is_lt();
+    fn clear(raw_points: &Self::Dependencies, index: GridPoint<Self>) {
+        raw_points.clear(Self::bounds(index));
+    }
+}
 
-                    // skip current point if another point's center is within our radius and we have lower priority
-                    if other.position().manhattan_dist(p.position()) < p.radius() + other.radius()
-                        && lower_priority
+impl<P: Reducible, const SIZE: u8, const SALT: u64, R: SeedableRng + RngCore + 'static>
+    Layer<ReducedUniformPoint<P, SIZE, SALT, R>>
+{
+    /// The point closest to `pos`, if there is one within `max_radius`.
+    ///
+    /// Scans rings of chunks of growing distance around `pos`. A point found in one ring can still be
+    /// further away than points in the next ring (e.g. one in a corner of the ring vs. one straight
+    /// across the next border), so scanning only stops once all points closer than the best one
+    /// are guaranteed to be in the scanned area. Of equally close points, the lowest position wins.
+    pub fn nearest(&self, pos: Point2d, max_radius: i64) -> Option<(Point2d, P)> {
+        type C<P, const SIZE: u8, const SALT: u64, R> = ReducedUniformPoint<P, SIZE, SALT, R>;
+        let center = C::<P, SIZE, SALT, R>::pos_to_grid(pos);
+        // Squared distances of far apart points don't fit into an `i64`.
+        let square = |d: u64| u128::from(d).pow(2);
+        let max_dist = square(max_radius.max(0).unsigned_abs());
+        let mut best: Option<(u128, Point2d, P)> = None;
+        for ring in 0.. {
+            let chunks = Bounds::point(center).pad(Point2d::splat(GridIndex::from_raw(ring)));
+            for index in chunks.iter_border() {
+                for p in self.get(index).points {
+                    let (x, y) = (
+                        p.position().x.abs_diff(pos.x),
+                        p.position().y.abs_diff(pos.y),
+                    );
+                    let dist = square(x).saturating_add(square(y));
+                    if dist <= max_dist
+                        && best
+                            .as_ref()
+                            .is_none_or(|(d, best, _)| (dist, p.position()) < (*d, *best))
                     {
-                        continue 'points;
+                        best = Some((dist, p.position(), p));
                     }
                 }
             }
-            points.push(p);
+            // All points within this distance of `pos` are in the chunks scanned so far.
+            let last = chunks.max - Point2d::splat(GridIndex::from_raw(1));
+            let scanned = Bounds {
+                min: C::<P, SIZE, SALT, R>::bounds(chunks.min).min,
+                max: C::<P, SIZE, SALT, R>::bounds(last).max,
+            };
+            let (below, above) = (pos - scanned.min, scanned.max - Point2d::splat(1) - pos);
+            let covered = below.x.min(below.y).min(above.x).min(above.y);
+            if covered >= max_radius
+                || best
+                    .as_ref()
+                    .is_some_and(|(d, ..)| *d <= square(covered.max(0).unsigned_abs()))
+            {
+                break;
+            }
         }
-        ReducedUniformPoint { points }
-    }
-
-    fn clear(raw_points: &Self::Dependencies, index: GridPoint<Self>) {
-        raw_points.clear(Self::bounds(index));
+        best.map(|(_, pos, p)| (pos, p))
     }
 }
 
-impl<P: Reducible, const SIZE: u8, const SALT: u64> Debug for ReducedUniformPoint<P, SIZE, SALT> {
+impl<P: Reducible, const SIZE: u8, const SALT: u64, R: SeedableRng + RngCore + 'static> Debug
+    for ReducedUniformPoint<P, SIZE, SALT, R>
+{
     fn debug(&self, bounds: Bounds) -> Vec<DebugContent> {
         self.points
             .iter()
@@ -101,6 +228,7 @@ impl<P: Reducible, const SIZE: u8, const SALT: u64> Debug for ReducedUniformPoin
                         DebugContent::Line(..) => {}
                         DebugContent::Circle { radius, .. } => *radius = 1.,
                         DebugContent::Text { .. } => {}
+                        DebugContent::Polygon { .. } => {}
                     }
                 }
                 debug
@@ -0,0 +1,70 @@
+use std::{borrow::Borrow as _, sync::Arc};
+
+use crate::{
+    Chunk, ChunkGrid as _, GridPoint, Layer, SparseGrid,
+    debug::Debug,
+    vec2::{Bounds, Point2d},
+};
+
+/// Chunks that have a lightweight summary (e.g. counts of things, or which
+/// areas are interesting), which can stay cached while the full chunk gets evicted.
+pub trait Summarize: Chunk + Debug {
+    /// The lightweight data of the chunk.
+    type Summary: Clone + Default + 'static;
+
+    /// Compute the summary of this chunk.
+    fn summarize(&self) -> Self::Summary;
+}
+
+/// A layer that only contains the [Summarize::Summary] of the chunks of another layer.
+///
+/// Summaries are never evicted automatically, while the full chunks are cached as usual by
+/// their own layer, and can also be evicted explicitly via [Layer::evict_full].
+/// Useful for e.g. maps, that need a little information about large areas.
+pub struct Summarized<C: Summarize>(pub C::Summary);
+
+impl<C: Summarize> Clone for Summarized<C> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<C: Summarize> Default for Summarized<C> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<C: Summarize> Chunk for Summarized<C> {
+    type LayerStore<T> = Arc<T>;
+    type Grid = SparseGrid<Self>;
+    type Dependencies = Layer<C>;
+    const SIZE: Point2d<u8> = C::SIZE;
+
+    fn compute_in_bounds(
+        full: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        Self(full.get(index.same_index()).summarize())
+    }
+
+    fn clear(full: &Self::Dependencies, index: GridPoint<Self>) {
+        full.layer.borrow().0.clear(index.same_index(), full);
+    }
+}
+
+impl<C: Summarize> Debug for Summarized<C> {}
+
+impl<C: Summarize> Layer<Summarized<C>> {
+    /// Get the summary of the chunk at `index`. If it isn't cached, the full chunk
+    /// is loaded to compute it.
+    pub fn get_summary(&self, index: GridPoint<C>) -> C::Summary {
+        self.get(index.same_index()).0
+    }
+
+    /// Remove the full chunk at `index` from its layer's cache, keeping only its summary.
+    pub fn evict_full(&self, index: GridPoint<C>) {
+        Summarized::<C>::clear(self, index.same_index());
+    }
+}
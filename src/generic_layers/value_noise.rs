@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use rand::prelude::*;
+
+use crate::{
+    Chunk, ChunkExt as _, Layer, RollingGrid, Seed, debug::Debug, rolling_grid::GridPoint,
+    vec2::Bounds, vec2::Point2d,
+};
+
+use super::rng_for_point;
+
+#[derive(PartialEq, Debug, Clone, Default)]
+/// A smoothly varying value in `0.0..=1.0` for every world coordinate, e.g. for elevation or moisture.
+///
+/// Each chunk corner has a random value (seeded by the corner's position, the [Seed] and the salt),
+/// and the values in between are interpolated. Neighboring chunks share their corners, so the values
+/// are continuous across chunk borders. Features are roughly the size of a chunk, use multiple
+/// layers with different `SIZE`s and add up their values for more detail.
+///
+/// All `2^SIZE * 2^SIZE` values of a chunk are computed ahead of time, so sampling is cheap,
+/// but large chunk sizes need a lot of memory.
+pub struct ValueNoise<const SIZE: u8, const SALT: u64> {
+    /// The world position of the first value.
+    pub min: Point2d,
+    /// The values of the chunk, row by row.
+    pub values: Arc<[f32]>,
+}
+
+impl<const SIZE: u8, const SALT: u64> Chunk for ValueNoise<SIZE, SALT> {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Seed;
+
+    const SIZE: Point2d<u8> = Point2d::splat(SIZE);
+
+    fn compute_in_bounds(
+        &seed: &Self::Dependencies,
+        index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        let corner = |x, y| {
+            rng_for_point::<SALT, _>(index.map(|i| i.0) + Point2d::new(x, y), seed).random::<f32>()
+        };
+        let [top_left, top_right] = [corner(0, 0), corner(1, 0)];
+        let [bottom_left, bottom_right] = [corner(0, 1), corner(1, 1)];
+        let side = (bounds.max.x - bounds.min.x) as f32;
+        let smooth = |offset: i64| {
+            let t = offset as f32 / side;
+            t * t * (3. - 2. * t)
+        };
+        let values = (bounds.min.y..bounds.max.y)
+            .flat_map(|y| {
+                let ty = smooth(y - bounds.min.y);
+                (bounds.min.x..bounds.max.x).map(move |x| {
+                    let tx = smooth(x - bounds.min.x);
+                    let top = top_left + (top_right - top_left) * tx;
+                    let bottom = bottom_left + (bottom_right - bottom_left) * tx;
+                    top + (bottom - top) * ty
+                })
+            })
+            .collect();
+        Self {
+            min: bounds.min,
+            values,
+        }
+    }
+}
+
+impl<const SIZE: u8, const SALT: u64> ValueNoise<SIZE, SALT> {
+    /// The value at a world position within this chunk.
+    /// Use [Layer::sample] for positions anywhere in the world.
+    #[track_caller]
+    pub fn sample(&self, pos: Point2d) -> f32 {
+        let local = pos - self.min;
+        let side = 1 << SIZE;
+        assert!(
+            (0..side).contains(&local.x) && (0..side).contains(&local.y),
+            "{pos:?} is not within the chunk starting at {:?}",
+            self.min
+        );
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "checked above to be within the chunk"
+        )]
+        self.values[(local.x + local.y * side) as usize]
+    }
+}
+
+impl<const SIZE: u8, const SALT: u64> Layer<ValueNoise<SIZE, SALT>> {
+    /// The value at any world position, loading the chunk containing it if necessary.
+    pub fn sample(&self, pos: Point2d) -> f32 {
+        self.get(ValueNoise::pos_to_grid(pos)).sample(pos)
+    }
+}
+
+impl<const SIZE: u8, const SALT: u64> Debug for ValueNoise<SIZE, SALT> {}
@@ -0,0 +1,145 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use rand::prelude::*;
+
+use crate::{
+    Chunk, ChunkExt as _, Dependencies, Layer, RollingGrid, Seed,
+    debug::{Debug, DebugContent, DynLayer},
+    rolling_grid::GridPoint,
+    vec2::{Bounds, Point2d},
+};
+
+use super::{ValueNoise, rng_for_point};
+
+/// Chunks that have a value at every world position within them, e.g. noise or a biome map.
+pub trait ScalarField: Chunk {
+    /// The value at a world position within this chunk.
+    fn value(&self, pos: Point2d) -> f32;
+}
+
+impl<const SIZE: u8, const SALT: u64> ScalarField for ValueNoise<SIZE, SALT> {
+    fn value(&self, pos: Point2d) -> f32 {
+        self.sample(pos)
+    }
+}
+
+/// How many samples per axis the density is averaged over for each chunk.
+const SAMPLES: i64 = 4;
+
+/// The dependencies of a [WeightedUniformPoint] layer.
+pub struct WeightedPointsDeps<F: ScalarField> {
+    /// The seed of the world.
+    pub seed: Seed,
+    /// The expected number of points of a chunk at each position.
+    pub density: Layer<F>,
+}
+
+impl<F: ScalarField + Debug> Dependencies for WeightedPointsDeps<F> {
+    fn debug(&self) -> Vec<&dyn DynLayer> {
+        vec![&self.density]
+    }
+
+    fn version(&self) -> u64 {
+        self.density.version()
+    }
+}
+
+/// Like [UniformPoint](super::UniformPoint), but the number of points varies with the
+/// value of the `density` layer, e.g. many settlements near rivers and few in the wilderness.
+///
+/// The density is the expected number of points per chunk, averaged over a few positions of
+/// each chunk. Negative values count as zero. A chunk with an average density of `2.3` gets
+/// two points, and a third one with a probability of `0.3`. Whether a point exists only depends
+/// on the density within its own chunk, so it doesn't matter which chunk requests it.
+pub struct WeightedUniformPoint<F, const SIZE: u8, const SALT: u64> {
+    /// The points of this chunk.
+    pub points: Arc<[Point2d]>,
+    density: PhantomData<fn() -> F>,
+}
+
+impl<F, const SIZE: u8, const SALT: u64> Default for WeightedUniformPoint<F, SIZE, SALT> {
+    fn default() -> Self {
+        Self {
+            points: Arc::new([]),
+            density: PhantomData,
+        }
+    }
+}
+
+impl<F, const SIZE: u8, const SALT: u64> Clone for WeightedUniformPoint<F, SIZE, SALT> {
+    fn clone(&self) -> Self {
+        Self {
+            points: self.points.clone(),
+            density: PhantomData,
+        }
+    }
+}
+
+impl<F, const SIZE: u8, const SALT: u64> PartialEq for WeightedUniformPoint<F, SIZE, SALT> {
+    fn eq(&self, other: &Self) -> bool {
+        self.points == other.points
+    }
+}
+
+impl<F, const SIZE: u8, const SALT: u64> std::fmt::Debug for WeightedUniformPoint<F, SIZE, SALT> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeightedUniformPoint")
+            .field("points", &self.points)
+            .finish()
+    }
+}
+
+impl<F: ScalarField + Debug, const SIZE: u8, const SALT: u64> Chunk
+    for WeightedUniformPoint<F, SIZE, SALT>
+{
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = WeightedPointsDeps<F>;
+
+    const SIZE: Point2d<u8> = Point2d::splat(SIZE);
+
+    fn compute_in_bounds(
+        WeightedPointsDeps { seed, density }: &Self::Dependencies,
+        index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        let size = bounds.max - bounds.min;
+        let total: f32 = Bounds {
+            min: Point2d::splat(0),
+            max: Point2d::splat(SAMPLES),
+        }
+        .iter()
+        .map(|sample| {
+            // The centers of a `SAMPLES * SAMPLES` grid within the chunk.
+            let pos = bounds.min + (sample * 2 + Point2d::splat(1)) * size / (SAMPLES * 2);
+            density.get(F::pos_to_grid(pos)).value(pos).max(0.)
+        })
+        .sum();
+        let expected = total / (SAMPLES * SAMPLES) as f32;
+        let mut rng = rng_for_point::<SALT, _>(index, *seed);
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "densities are small numbers of points"
+        )]
+        let n = expected as usize + usize::from(rng.random::<f32>() < expected.fract());
+        Self {
+            points: std::iter::from_fn(|| Some(Self::sample_point(index, &mut rng)))
+                .take(n)
+                .collect(),
+            density: PhantomData,
+        }
+    }
+
+    fn clear(WeightedPointsDeps { density, .. }: &Self::Dependencies, index: GridPoint<Self>) {
+        density.clear(Self::bounds(index));
+    }
+}
+
+impl<F, const SIZE: u8, const SALT: u64> Debug for WeightedUniformPoint<F, SIZE, SALT> {
+    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
+        self.points
+            .iter()
+            .map(|&center| DebugContent::Circle { center, radius: 1. })
+            .collect()
+    }
+}
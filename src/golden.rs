@@ -0,0 +1,113 @@
+//! Helpers for locking down the output of layers in tests.
+//!
+//! Generate a fixed region with [checksums] (or [snapshot] to see the actual contents)
+//! and compare it against a committed file with [assert_golden]. When the generation is changed intentionally, rerun the tests with the
+//! `UPDATE_GOLDEN` environment variable set to update the files.
+
+use std::{
+    fmt::Write as _,
+    hash::{Hash, Hasher},
+    path::Path,
+};
+
+use crate::{Chunk, ChunkExt as _, GridBounds, Layer, vec2::Bounds};
+
+/// A [Hasher] whose output only depends on the hashed data, unlike
+/// [DefaultHasher](std::hash::DefaultHasher), which is allowed to change between Rust releases.
+///
+/// This is the 64 bit FNV-1a hash. Note that [Hash] impls write integers in native endianness
+/// and `usize` lengths, so the hashes can still differ between platforms.
+#[derive(Debug, Clone)]
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Hash a value with [StableHasher].
+pub fn content_hash(value: &impl Hash) -> u64 {
+    let mut hasher = StableHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Generate all chunks in `region` and list the [content_hash] of each of them,
+/// one `x y hash` line per chunk, sorted by chunk index.
+pub fn checksums<C: Chunk + Hash>(layer: &Layer<C>, region: GridBounds<C>) -> String {
+    let mut indices: Vec<_> = region.iter().collect();
+    indices.sort();
+    let mut out = String::new();
+    for index in indices {
+        let hash = content_hash(&layer.get(index));
+        writeln!(out, "{} {} {hash:016x}", index.x.0, index.y.0).unwrap();
+    }
+    out
+}
+
+/// Generate all chunks touching `bounds` (in world coordinates) and list their [Debug](std::fmt::Debug)
+/// output, one `x y chunk` line per chunk, sorted by chunk index.
+///
+/// Unlike [checksums], the differences show what actually changed, which makes it easier to
+/// review intentional changes, but the output of large regions is large, too.
+pub fn snapshot<C: Chunk + std::fmt::Debug>(layer: &Layer<C>, bounds: Bounds) -> String {
+    let mut indices: Vec<_> = C::bounds_to_grid(bounds).iter().collect();
+    indices.sort();
+    let mut out = String::new();
+    for index in indices {
+        let chunk = layer.get(index);
+        writeln!(out, "{} {} {chunk:?}", index.x.0, index.y.0).unwrap();
+    }
+    out
+}
+
+/// Check that `actual` is the same as the contents of the file at `path`.
+/// If the `UPDATE_GOLDEN` environment variable is set, the file is overwritten instead.
+#[track_caller]
+pub fn assert_golden(actual: &str, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(path, actual)
+            .unwrap_or_else(|err| panic!("could not write {}: {err}", path.display()));
+        return;
+    }
+    let expected = std::fs::read_to_string(path).unwrap_or_else(|err| {
+        panic!(
+            "could not read {}: {err}\nrun with UPDATE_GOLDEN=1 to create it",
+            path.display()
+        )
+    });
+    if expected != actual {
+        let first_diff = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or(expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "{} does not match the generated output, first difference in line {}\n\
+             expected: {:?}\n  actual: {:?}\n\
+             run with UPDATE_GOLDEN=1 if the change is intentional",
+            path.display(),
+            first_diff + 1,
+            expected.lines().nth(first_diff),
+            actual.lines().nth(first_diff),
+        );
+    }
+}
@@ -13,7 +13,7 @@
 //! As an example, here's a layer that generates a point at its center:
 //!
 //! ```rust
-//! use layer_proc_gen::{Chunk, GridPoint, Point2d, debug::DynLayer, ChunkExt as _};
+//! use layer_proc_gen::{Bounds, Chunk, GridPoint, Point2d, RollingGrid, debug::DynLayer, ChunkExt as _};
 //!
 //! #[derive(Clone, Default)]
 //! struct MyChunk {
@@ -22,14 +22,12 @@
 //!
 //! impl Chunk for MyChunk {
 //!     type LayerStore<T> = std::sync::Arc<T>;
+//!     type Grid = RollingGrid<Self>;
 //!     type Dependencies = ();
-//!     fn compute(&(): &(), index: GridPoint<Self>) -> Self {
-//!         let center = Self::bounds(index).center();
+//!     fn compute_in_bounds(&(): &(), index: GridPoint<Self>, bounds: Bounds) -> Self {
+//!         let center = bounds.center();
 //!         MyChunk { center }
 //!     }
-//!     fn clear(&(): &(), index: GridPoint<Self>) {
-//!         // No dependencies to clear things from
-//!     }
 //! }
 //! ```
 //!
@@ -39,23 +37,38 @@
 #![warn(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
 #![deny(missing_docs)]
 
-use std::{borrow::Borrow, ops::Deref};
+use std::{
+    borrow::Borrow,
+    ops::Deref,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use rand::RngCore;
 
-use debug::DynLayer;
-use rolling_grid::RollingGrid;
-pub use rolling_grid::{GridIndex, GridPoint};
+pub use async_chunk::AsyncChunk;
+use debug::{DependencySnapshot, DynLayer};
+pub use rolling_grid::{ChunkGrid, GridBounds, GridIndex, GridPoint, GridStats, RollingGrid};
+pub use sparse_grid::SparseGrid;
+pub use sync_rolling_grid::SyncRollingGrid;
 pub use vec2::{Bounds, Point2d};
+use vec2::{CoordMode, Line};
+pub use vision_range::VisionRange;
 
+pub mod builder;
 pub mod debug;
 pub mod generic_layers;
+pub mod golden;
+pub mod persist;
+pub mod prefetch;
+pub mod rng;
 
 #[macro_export]
 /// Generate a struct where all fields are wrapped in `Layer`
 macro_rules! deps {
-    ($(#[$meta:meta])* struct $name:ident {$($field:ident: $ty:ty,)*}) => {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident {$($field_vis:vis $field:ident: $ty:ty,)*}) => {
         $(#[$meta])*
-        struct $name {
-            $($field: Layer<$ty>,)*
+        $vis struct $name {
+            $($field_vis $field: Layer<$ty>,)*
         }
         impl $crate::Dependencies for $name {
             fn debug(&self) -> Vec<&dyn $crate::debug::DynLayer> {
@@ -64,22 +77,138 @@ macro_rules! deps {
                 } = self;
                 vec![ $($field,)*]
             }
+
+            fn version(&self) -> u64 {
+                let $name {
+                    $($field,)*
+                } = self;
+                $crate::combine_versions([$($field.version(),)*])
+            }
+        }
+        // The higher-ranked bounds only make this impl not apply if a layer can't be built,
+        // instead of failing to compile.
+        impl $crate::builder::Build for $name
+        where
+            $(for<'a> Layer<$ty>: $crate::builder::Build,)*
+        {
+            fn build(
+                builder: &mut $crate::builder::LayerBuilder,
+            ) -> Result<Self, $crate::builder::BuildError> {
+                Ok($name {
+                    $($field: $crate::builder::Build::build(builder)?,)*
+                })
+            }
         }
     }
 }
 
+#[macro_export]
+/// Create a [GridPoint] from raw chunk indices, e.g. `point!(3, -4)`.
+/// The chunk type can be specified explicitly if it can't be inferred: `point!(MyChunk; 3, -4)`.
+macro_rules! point {
+    ($chunk:ty; $x:expr, $y:expr $(,)?) => {
+        $crate::GridPoint::<$chunk>::from_raw($x, $y)
+    };
+    ($x:expr, $y:expr $(,)?) => {
+        $crate::GridPoint::from_raw($x, $y)
+    };
+}
+
 /// A struct that defines the dependencies of your [Chunk].
 /// Usually generated for structs via the [deps] macro, but you can manually define
 /// it in case you have non-[Layer] dependencies.
 ///
 /// If you layer has no dependencies, you can use the `()` type instead.
 pub trait Dependencies {
+    /// Whether there are [Layer]s within the dependencies. If not, chunks using these
+    /// dependencies don't need to implement [Chunk::clear], as there is nothing to clear.
+    const HAS_LAYERS: bool = true;
+
     /// For runtime debugging of your layers, you should return references to each of the
     /// layer types within your dependencies.
     fn debug(&self) -> Vec<&dyn DynLayer>;
+
+    /// Changes whenever something the chunks using these dependencies are computed from changes,
+    /// e.g. via [Layer::bump_version]. Cached chunks computed with a different version get recomputed.
+    ///
+    /// Defaults to [combining](combine_versions) the [versions](DynLayer::version) of the layers
+    /// returned by [Dependencies::debug]. Override it to include the version of non-layer
+    /// dependencies (e.g. an editable configuration). Grids only call it again after a layer
+    /// got bumped, so it doesn't need to be fast.
+    fn version(&self) -> u64 {
+        if !Self::HAS_LAYERS {
+            return 0;
+        }
+        combine_versions(self.debug().iter().map(|layer| layer.version()))
+    }
+}
+
+/// Combine the versions of multiple dependencies into one, e.g. for [Dependencies::version].
+///
+/// Like a hash, the result changes if any of the versions changes, even if another one changes
+/// in the opposite direction. It is zero if all versions are zero, so chunks that were cached before
+/// anything got bumped (e.g. [deserialized](RollingGrid) ones) stay valid.
+pub fn combine_versions(versions: impl IntoIterator<Item = u64>) -> u64 {
+    versions.into_iter().fold(0, |combined, version| {
+        (combined ^ version).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+    })
+}
+
+/// Incremented by every [Layer::bump_version] call, so [VersionCache]s know when to recompute.
+/// It is shared by all worlds, so bumps in one also make the others re-check their versions.
+static BUMPS: AtomicU64 = AtomicU64::new(0);
+
+/// A version that only gets recomputed after any layer got bumped, so checking it
+/// on every cache hit doesn't walk through all dependency layers.
+pub(crate) struct VersionCache {
+    /// The [BUMPS] the version was computed at, or one of the special values below.
+    bumps: AtomicU64,
+    version: AtomicU64,
+}
+
+impl Default for VersionCache {
+    fn default() -> Self {
+        Self {
+            bumps: AtomicU64::new(Self::NEVER),
+            version: AtomicU64::new(0),
+        }
+    }
+}
+
+impl VersionCache {
+    /// The version has never been computed.
+    const NEVER: u64 = u64::MAX;
+    /// Another thread is storing a new version.
+    const UPDATING: u64 = u64::MAX - 1;
+
+    /// The cached version, or the result of `compute` if anything got bumped since it was cached.
+    pub(crate) fn get(&self, compute: impl FnOnce() -> u64) -> u64 {
+        let bumps = BUMPS.load(Ordering::Acquire);
+        let cached = self.bumps.load(Ordering::Acquire);
+        if cached == bumps {
+            let version = self.version.load(Ordering::Acquire);
+            // Otherwise another thread replaced the version while we were reading it.
+            if self.bumps.load(Ordering::Acquire) == bumps {
+                return version;
+            }
+        }
+        let version = compute();
+        // If another thread is already updating the cache, leave it to that one.
+        if self
+            .bumps
+            .compare_exchange(cached, Self::UPDATING, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            self.version.store(version, Ordering::Release);
+            self.bumps.store(bumps, Ordering::Release);
+        }
+        version
+    }
 }
 
 impl Dependencies for () {
+    const HAS_LAYERS: bool = false;
+
     fn debug(&self) -> Vec<&dyn DynLayer> {
         vec![]
     }
@@ -89,22 +218,67 @@ impl<C: Chunk + debug::Debug> Dependencies for Layer<C> {
     fn debug(&self) -> Vec<&dyn DynLayer> {
         vec![self]
     }
+
+    fn version(&self) -> u64 {
+        Layer::version(self)
+    }
 }
 
 /// Wrapper around a [`u64`] seed so it can be used as [`Dependencies`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct Seed(pub u64);
 
 impl Dependencies for Seed {
+    const HAS_LAYERS: bool = false;
+
     fn debug(&self) -> Vec<&dyn crate::debug::DynLayer> {
         vec![]
     }
 }
 
+/// [Dependencies] that can be recreated with a different [Seed], see [Layer::reseed].
+///
+/// Implemented for [Seed], `()` and layers whose dependencies implement it. Structs
+/// created via the [deps] macro need to implement it manually by reseeding each field.
+pub trait Reseed: Dependencies {
+    /// Create fresh dependencies (with empty caches) that use `seed` instead of the current seed.
+    fn reseeded(&self, seed: Seed) -> Self;
+}
+
+impl Reseed for () {
+    fn reseeded(&self, _seed: Seed) -> Self {}
+}
+
+impl Reseed for Seed {
+    fn reseeded(&self, seed: Seed) -> Self {
+        seed
+    }
+}
+
+impl<C: Chunk + debug::Debug> Reseed for Layer<C>
+where
+    C::Dependencies: Reseed,
+{
+    /// Create a new layer with new dependencies. Layers that were shared between
+    /// several dependents are duplicated.
+    fn reseeded(&self, seed: Seed) -> Self {
+        Layer::new(self.deref().reseeded(seed))
+    }
+}
+
 /// The entry point to access the chunks of a layer.
 ///
 /// It exposes various convenience accessors, like iterating over areas in
 /// chunk or world coordinates.
+///
+/// A layer owns its caches and (via [Chunk::Dependencies]) its dependency layers,
+/// so it is the handle to an entire world. You can have as many independent worlds
+/// (e.g. with different [Seed]s) in one process as you want: they share no chunks or caches.
+/// The only thing they share is a counter of [Layer::bump_version] calls, so after a bump
+/// in any world, every layer walks through the versions of its dependencies once more on its
+/// next access. Only layers that actually depend on the bumped one recompute chunks.
 pub struct Layer<C: Chunk> {
     layer: Store<C>,
 }
@@ -133,8 +307,17 @@ impl<C: Chunk> Layer<C> {
     /// Create a new layer, manually specifying the dependencies.
     /// This is useful if you want to share dependencies with another layer.
     pub fn new(value: C::Dependencies) -> Self {
+        Self::with_grid(C::Grid::default(), value)
+    }
+
+    /// Create a new layer with a manually created cache, e.g. a [RollingGrid::with_size]
+    /// chosen at runtime.
+    pub fn with_grid(grid: C::Grid, value: C::Dependencies) -> Self {
+        // Instantiate `clear`, so its check for a missing implementation runs
+        // even if nothing ever clears chunks of this layer.
+        let _: fn(&C::Dependencies, GridPoint<C>) = C::clear;
         Layer {
-            layer: Store::<C>::from((RollingGrid::default(), value)),
+            layer: Store::<C>::from((grid, value, AtomicU64::new(0), VersionCache::default())),
         }
     }
 }
@@ -162,7 +345,8 @@ where
 #[expect(type_alias_bounds)]
 type Store<C: Chunk> = C::LayerStore<Tuple<C>>;
 #[expect(type_alias_bounds)]
-type Tuple<C: Chunk> = (RollingGrid<C>, C::Dependencies);
+/// The cache, the dependencies, the number of [Layer::bump_version] calls and the [Layer::version].
+type Tuple<C: Chunk> = (C::Grid, C::Dependencies, AtomicU64, VersionCache);
 
 impl<C: Chunk> Layer<C> {
     /// Eagerly compute all chunks in the given bounds (in world coordinates).
@@ -171,23 +355,251 @@ impl<C: Chunk> Layer<C> {
     #[track_caller]
     pub fn ensure_loaded_in_bounds(&self, chunk_bounds: Bounds) {
         let indices = C::bounds_to_grid(chunk_bounds);
-        let mut create_indices: Vec<_> = indices.iter().collect();
-        let center = indices.center();
-        // Sort by distance to center, so we load the closest ones first
-        // Difference to
-        create_indices.sort_by_cached_key(|&index| index.dist_squared(center));
-        for index in create_indices {
+        // Load the ones closest to the center first
+        for index in indices.iter_spiral(indices.center()) {
+            self.get(index);
+        }
+    }
+
+    /// Find out which chunks [Layer::ensure_loaded_in_bounds] would compute and which
+    /// ones it would find in the cache, without loading anything.
+    ///
+    /// This only looks at the chunks of this layer, not at the dependency chunks computing them needs.
+    pub fn plan_load(&self, chunk_bounds: Bounds) -> LoadPlan<C> {
+        let grid = &self.layer.borrow().0;
+        let (cached, to_compute) = C::bounds_to_grid(chunk_bounds)
+            .iter()
+            .partition(|&index| grid.contains(index));
+        LoadPlan { to_compute, cached }
+    }
+
+    /// Same as [Layer::ensure_loaded_in_bounds], but reports an error instead of panicking
+    /// if any of the chunks are outside of the world coordinates.
+    ///
+    /// Also reports [LayerError::OverlapExceeded] if the bounds are too large for the
+    /// [Chunk::Grid] to hold all their chunks at the same time (see [ChunkGrid::first_overflowing]),
+    /// so loading the later chunks would evict earlier ones. Callers can then load a smaller
+    /// area or use a larger grid (see [Layer::with_grid]).
+    ///
+    /// Both are checked before loading anything, so on errors the cache is not touched.
+    pub fn try_ensure_loaded_in_bounds(&self, chunk_bounds: Bounds) -> Result<(), LayerError> {
+        let indices = C::bounds_to_grid(chunk_bounds);
+        // The extremes are the first to overflow.
+        C::checked_bounds(indices.min)?;
+        C::checked_bounds(indices.max - Point2d::splat(GridIndex::from_raw(1)))?;
+        if let Some(index) = self.layer.borrow().0.first_overflowing(indices) {
+            return Err(LayerError::OverlapExceeded {
+                layer: std::any::type_name::<C>(),
+                index: index.map(|i| i.0),
+                span: (indices.max - indices.min).map(|i| i.0),
+            });
+        }
+        self.ensure_loaded_in_bounds(chunk_bounds);
+        Ok(())
+    }
+
+    /// Same as [Layer::ensure_loaded_in_bounds], but loads the chunks closest to
+    /// `center` (in world coordinates) first instead of the ones closest to the center of
+    /// the bounds. Useful if the bounds are not centered on the point of interest, e.g.
+    /// when prefetching more in the direction a player is moving.
+    #[track_caller]
+    pub fn ensure_loaded_in_bounds_from(&self, chunk_bounds: Bounds, center: Point2d) {
+        // Taken instead of borrowed, so computing the chunks can call this again (with a fresh buffer).
+        let mut order = LOAD_ORDER_SCRATCH.take();
+        order.clear();
+        order.extend(
+            C::bounds_to_grid(chunk_bounds)
+                .iter()
+                .enumerate()
+                .map(|(i, index)| {
+                    let dist = C::bounds(index).center().dist_squared(center);
+                    (dist, i, index.map(|i| i.0))
+                }),
+        );
+        // The position in `iter` breaks ties, so this is the same order as a stable sort by distance.
+        order.sort_unstable();
+        for &(_, _, index) in &order {
+            self.get(index.map(GridIndex::from_raw));
+        }
+        LOAD_ORDER_SCRATCH.set(order);
+    }
+
+    /// Whether all chunks touching the given bounds (in world coordinates) are currently cached.
+    pub fn is_loaded_in_bounds(&self, chunk_bounds: Bounds) -> bool {
+        self.is_loaded_in_grid_bounds(C::bounds_to_grid(chunk_bounds))
+    }
+
+    /// Whether all chunks in the given bounds (in chunk grid indices) are currently cached.
+    pub fn is_loaded_in_grid_bounds(&self, range: GridBounds<C>) -> bool {
+        range
+            .iter()
+            .all(|index| self.layer.borrow().0.contains(index))
+    }
+
+    /// Eagerly compute all chunks in the given regions (in chunk grid indices), e.g. the
+    /// regions a player explored according to a save game. This is meant to be run
+    /// during a loading screen, so the first frames after loading don't stall on generating
+    /// chunks.
+    ///
+    /// After each chunk, `progress` is called with the number of chunks handled so far and
+    /// the total number of chunks in all regions. Chunks in overlapping regions count multiple times.
+    ///
+    /// Note that the regions should fit into the [Chunk::Grid] at the same time, otherwise
+    /// later regions evict earlier ones.
+    pub fn warm(&self, regions: &[GridBounds<C>], mut progress: impl FnMut(usize, usize)) {
+        let total = regions.iter().map(GridBounds::index_count).sum();
+        let mut done = 0;
+        for region in regions {
+            // Load the closest ones first, like `ensure_loaded_in_bounds`.
+            for index in region.iter_spiral(region.center()) {
+                self.get(index);
+                done += 1;
+                progress(done, total);
+            }
+        }
+    }
+
+    /// Like [Layer::ensure_loaded_in_bounds], but computes the chunks on `threads` threads.
+    ///
+    /// Caches can't be shared between threads, so every thread gets its own copy of the
+    /// dependency layers from `dependencies`, which should create the same dependencies this layer has
+    /// (e.g. with the same [Seed]). Threads take the next chunk to compute from a shared queue whenever they
+    /// are done with one, computing its dependencies on demand as usual. As chunks only depend on their
+    /// dependencies, the result is identical to computing them on a single thread.
+    ///
+    /// Only the chunks of this layer are stored in its cache afterwards, dependency chunks
+    /// are dropped together with the threads' copies of the dependencies, and neighbouring chunks
+    /// computed by different threads compute their shared dependency chunks twice.
+    pub fn ensure_loaded_in_bounds_parallel(
+        &self,
+        chunk_bounds: Bounds,
+        threads: std::num::NonZeroUsize,
+        dependencies: impl Fn() -> C::Dependencies + Sync,
+    ) where
+        C: Send,
+    {
+        let indices = C::bounds_to_grid(chunk_bounds);
+        let indices: Vec<_> = indices
+            .iter_spiral(indices.center())
+            .filter(|&index| !self.layer.borrow().0.contains(index))
+            .collect();
+        // Raw positions, so they can be shared with the threads even if `C` is not `Sync`.
+        let raw: Vec<Point2d> = indices.iter().map(|index| index.map(|i| i.0)).collect();
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let mut chunks: Vec<(usize, C)> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.get())
+                .map(|_| {
+                    scope.spawn(|| {
+                        let worker = Layer::<C>::new(dependencies());
+                        let mut chunks = vec![];
+                        loop {
+                            let i = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                            let Some(&index) = raw.get(i) else {
+                                break chunks;
+                            };
+                            chunks.push((i, worker.get(index.map(GridIndex::from_raw))));
+                        }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|err| std::panic::resume_unwind(err))
+                })
+                .collect()
+        });
+        // Insert in the same order as `ensure_loaded_in_bounds` computes them.
+        chunks.sort_by_key(|&(i, _)| i);
+        for (i, chunk) in chunks {
+            self.layer.borrow().0.set(self, indices[i], chunk);
+        }
+    }
+
+    /// Move the loaded area (e.g. a vision range) from `old` to `new`: compute the chunks
+    /// that are only in `new` and [clear](ChunkGrid::clear) the ones that are only in `old`.
+    /// Chunks in both are not touched, so for a slowly moving camera the work per frame only depends
+    /// on the length of the border of the loaded area, not on its size.
+    pub fn update_loaded_bounds(&self, old: GridBounds<C>, new: GridBounds<C>) {
+        let (load, release) = Bounds::difference(old, new);
+        for index in release {
+            self.layer.borrow().0.clear(index, self)
+        }
+        for index in load {
             self.get(index);
         }
     }
 
+    /// Switch to a different world seed at runtime (e.g. from a menu) without recreating
+    /// the layer's owner. Drops all cached chunks of this layer and its dependencies
+    /// and then [warms](Layer::warm) `regions` (e.g. the currently visible area), so the new
+    /// world streams in. Pass no regions to load chunks lazily instead.
+    ///
+    /// Other clones of this layer keep using the old seed and caches.
+    pub fn reseed(&mut self, seed: Seed, regions: &[GridBounds<C>])
+    where
+        C::Dependencies: Reseed,
+    {
+        *self = Layer::new(self.deref().reseeded(seed));
+        self.warm(regions, |_, _| {});
+    }
+
     /// Eagerly unload all chunks in the given bounds (in world coordinates).
+    ///
+    /// The counterpart of [Layer::ensure_loaded_in_bounds]: [Chunk::clear] recursively unloads
+    /// the dependency chunks they were computed from, too. Chunks are not reference counted, so
+    /// dependency chunks that other loaded chunks were computed from are unloaded as well, and get
+    /// recomputed when needed again. Not calling this is fine, the [RollingGrid] has a fixed
+    /// capacity and evicts the least recently used chunks.
     pub fn clear(&self, chunk_bounds: Bounds) {
         for index in C::bounds_to_grid(chunk_bounds).iter() {
             self.layer.borrow().0.clear(index, self)
         }
     }
 
+    /// Panics if this layer or any of its dependency layers (recursively) still has chunks cached.
+    ///
+    /// A correctness check for the [Chunk::clear] implementations of custom layers: after
+    /// [clearing](Layer::clear) everything that was loaded (and nothing else was loaded into the
+    /// dependency layers directly), no chunks should be left. Leftover dependency chunks mean some
+    /// [Chunk::clear] doesn't clear all the dependencies its chunks were computed from.
+    #[track_caller]
+    pub fn assert_balanced(&self) {
+        fn leftovers(
+            layer: &dyn DynLayer,
+            seen: &mut Vec<(usize, std::any::TypeId)>,
+            out: &mut Vec<String>,
+        ) {
+            if seen.contains(&layer.ident()) {
+                return;
+            }
+            seen.push(layer.ident());
+            let loaded: Vec<Bounds> = layer.iter_all_loaded().map(|(bounds, _)| bounds).collect();
+            if let Some(first) = loaded.first() {
+                out.push(format!(
+                    "{} ({} chunks, e.g. at {first:?})",
+                    layer.name(),
+                    loaded.len()
+                ));
+            }
+            for dep in layer.deps() {
+                leftovers(dep, seen, out);
+            }
+        }
+        let mut out = vec![];
+        let mut seen = vec![];
+        let own = self.layer.borrow().0.iter_all_loaded().count();
+        if own > 0 {
+            out.push(format!("{} ({own} chunks)", debug::short_type_name::<C>()));
+        }
+        for dep in self.debug() {
+            leftovers(dep, &mut seen, &mut out);
+        }
+        assert!(out.is_empty(), "chunks still cached: {}", out.join(", "));
+    }
+
     /// Manually (without calling `compute`) set a chunk in the cache.
     ///
     /// This violates all the nice properties like the fact that layers
@@ -197,19 +609,124 @@ impl<C: Chunk> Layer<C> {
     /// lead to recomputed chunks being different from non-recomputed chunks.
     ///
     /// TLDR: only call this if you have called `clear` on everything that depended
-    /// on this one.
+    /// on this one, or call [Layer::bump_version] afterwards.
     pub fn incoherent_override_cache(&self, index: GridPoint<C>, val: C) {
-        self.layer
-            .borrow()
-            .0
-            .incoherent_override_cache(self, index, val)
+        self.layer.borrow().0.set(self, index, val)
+    }
+
+    /// Mark the chunks of this layer as changed, e.g. after overriding some of them or after
+    /// changing a parameter in an editor. All cached chunks of layers depending on this one
+    /// (directly or indirectly) are recomputed the next time they are requested.
+    ///
+    /// The chunks of this layer itself are not touched, [clear](Layer::clear) or
+    /// [override](Layer::incoherent_override_cache) them as needed. Stale chunks stay in the
+    /// cache until they are requested, so e.g. [Layer::plan_load] still reports them as cached.
+    ///
+    /// Afterwards, all layers (also those of other worlds) check the versions of their
+    /// dependencies once more, but only the ones depending on this layer recompute chunks.
+    pub fn bump_version(&self) {
+        self.layer.borrow().2.fetch_add(1, Ordering::Relaxed);
+        BUMPS.fetch_add(1, Ordering::Release);
+    }
+
+    /// Changes with every [Layer::bump_version] call on this layer and its dependencies,
+    /// see [combine_versions]. Only walks through the dependency layers after anything got bumped.
+    pub fn version(&self) -> u64 {
+        let (_, deps, bumps, version) = self.layer.borrow();
+        version.get(|| combine_versions([bumps.load(Ordering::Relaxed), deps.version()]))
     }
 
     /// Get a chunk or generate it if it wasn't already cached.
+    ///
+    /// The chunk is returned by value (cloned out of the cache), independently
+    /// of [Chunk::LayerStore], so it can be held for as long as you want, even
+    /// beyond the lifetime of the layer. Chunks that are expensive to clone should
+    /// put their data behind an [Arc](std::sync::Arc) to make this cheap.
     pub fn get(&self, index: GridPoint<C>) -> C {
+        self.get_with_freshness(index).0
+    }
+
+    /// Same as [Layer::get], but reports an error instead of panicking if the chunk is
+    /// outside of the world coordinates.
+    ///
+    /// Note that this only checks the requested chunk, the dependency chunks that
+    /// [Chunk::compute] accesses are fetched as usual.
+    pub fn try_get(&self, index: GridPoint<C>) -> Result<C, LayerError> {
+        C::checked_bounds(index)?;
+        Ok(self.get(index))
+    }
+
+    /// Same as [Layer::get], but also reports whether the chunk was freshly computed
+    /// or already cached. Useful for doing work exactly once per chunk, like spawning
+    /// entities when a chunk gets created.
+    pub fn get_with_freshness(&self, index: GridPoint<C>) -> (C, Freshness) {
+        if INSPECT.with_borrow(Option::is_some) {
+            return self.get_inspected(index);
+        }
         self.layer.borrow().0.get(index, self)
     }
 
+    /// Find out which dependency chunks computing the chunk at `index` reads, e.g. for a
+    /// debug view of a chunk. This does not modify any caches: loaded dependency chunks
+    /// are copied, and missing ones are computed without caching them and reported as `None`.
+    /// Only the dependencies read directly by [Chunk::compute] are reported, not their
+    /// own dependencies.
+    pub fn inspect_deps(&self, index: GridPoint<C>) -> Vec<DependencySnapshot> {
+        let _inspect = Inspect(INSPECT.replace(Some(Default::default())));
+        C::compute(self, index);
+        INSPECT.with_borrow_mut(|inspect| std::mem::take(&mut inspect.as_mut().unwrap().snapshots))
+    }
+
+    /// [Layer::get_with_freshness] while [Layer::inspect_deps] is running.
+    fn get_inspected(&self, index: GridPoint<C>) -> (C, Freshness) {
+        let chunk = self.layer.borrow().0.peek(index);
+        INSPECT.with_borrow_mut(|inspect| {
+            let inspect = inspect.as_mut().unwrap();
+            if inspect.depth == 0 {
+                inspect
+                    .snapshots
+                    .push(DependencySnapshot::new(index, chunk.clone()));
+            }
+            inspect.depth += 1;
+        });
+        let result = match chunk {
+            Some(chunk) => (chunk, Freshness::Cached),
+            None => (compute::<C>(self, index), Freshness::Created),
+        };
+        INSPECT.with_borrow_mut(|inspect| inspect.as_mut().unwrap().depth -= 1);
+        result
+    }
+
+    /// Get a chunk or compute it, assuming that all the dependency chunks
+    /// it needs have already been loaded (e.g. by prefetching them via
+    /// [Layer::ensure_loaded_in_bounds] on the dependency layers).
+    ///
+    /// With debug assertions enabled this panics if computing the chunk needs a dependency
+    /// chunk that is not loaded. Without them, the missing chunks are computed as usual.
+    #[track_caller]
+    pub fn get_shallow(&self, index: GridPoint<C>) -> C {
+        let grid = &self.layer.borrow().0;
+        // While inspecting, nothing may be cached.
+        if grid.contains(index) || INSPECT.with_borrow(Option::is_some) {
+            return self.get(index);
+        }
+        let chunk = compute_shallow::<C>(self, index);
+        grid.set(self, index, chunk.clone());
+        chunk
+    }
+
+    /// The cache backing this layer, as chosen via [Chunk::Grid].
+    pub fn grid(&self) -> &C::Grid {
+        &self.layer.borrow().0
+    }
+
+    /// All chunks that are currently cached, with their index, in no particular order,
+    /// e.g. for drawing a minimap of everything generated so far.
+    /// Each chunk is yielded once, even if other chunks share its grid cell.
+    pub fn loaded(&self) -> impl Iterator<Item = (GridPoint<C>, C)> + '_ {
+        self.grid().iter_all_loaded()
+    }
+
     /// Get an iterator over all chunks that touch the given bounds (in world coordinates)
     pub fn get_range(&self, range: Bounds) -> impl Iterator<Item = C> + '_ {
         let range = C::bounds_to_grid(range);
@@ -218,26 +735,306 @@ impl<C: Chunk> Layer<C> {
 
     /// Get an iterator over chunks as given by the bounds (in chunk grid indices).
     /// Chunks will be generated on the fly.
-    pub fn get_grid_range(&self, range: Bounds<GridIndex<C>>) -> impl Iterator<Item = C> + '_ {
+    pub fn get_grid_range(&self, range: GridBounds<C>) -> impl Iterator<Item = C> + '_ {
         // TODO: first request generation, then iterate to increase parallelism
         range.iter().map(move |pos| self.get(pos))
     }
 
+    /// Lazily generate and yield all chunks that touch the given bounds (in world coordinates),
+    /// together with their index, row by row. Useful for e.g. exporting a large area.
+    ///
+    /// Chunks are only retained as long as the [Chunk::Grid] keeps them, so scanning areas larger than a
+    /// [RollingGrid] does not grow memory use, while a [SparseGrid] keeps every scanned chunk.
+    pub fn scan(&self, range: Bounds) -> impl Iterator<Item = (GridPoint<C>, C)> + '_ {
+        C::bounds_to_grid(range)
+            .iter()
+            .map(move |index| (index, self.get(index)))
+    }
+
+    /// Call `f` for each chunk that touches the given bounds (in world coordinates),
+    /// together with its index. Chunks will be generated on the fly.
+    ///
+    /// In contrast to [Layer::get_range] no chunk outlives the call of `f`, which is convenient
+    /// for scans that only need to look at each chunk briefly, e.g. to search a neighborhood.
+    pub fn with_range(&self, range: Bounds, mut f: impl FnMut(GridPoint<C>, &C)) {
+        for index in C::bounds_to_grid(range).iter() {
+            f(index, &self.get(index))
+        }
+    }
+
+    /// Collect the line segments of the chunk at `index` and all its neighbors
+    /// that overlap the chunk at `index`, clipped to that chunk's bounds.
+    ///
+    /// This is useful for rendering a chunk of lines that may have been produced by
+    /// another chunk. Assumes that no chunk produces lines that reach beyond its
+    /// [moore neighborhood](ChunkExt::moore_neighborhood).
+    pub fn clipped_lines<I: IntoIterator<Item = Line>>(
+        &self,
+        index: GridPoint<C>,
+        lines: impl Fn(C) -> I,
+    ) -> Vec<Line> {
+        let bounds = C::bounds(index);
+        C::moore_neighborhood(index)
+            .into_iter()
+            .flatten()
+            .flat_map(|index| lines(self.get(index)))
+            .filter_map(|line| line.clip(bounds))
+            .collect()
+    }
+
     /// Get a 3x3 array of chunks around a specific chunk
     pub fn get_moore_neighborhood(&self, index: GridPoint<C>) -> [[C; 3]; 3] {
         C::moore_neighborhood(index).map(|line| line.map(|index| self.get(index)))
     }
 }
 
+thread_local! {
+    /// The chunks to load in [Layer::ensure_loaded_in_bounds_from], kept around to avoid
+    /// allocating every time a moving camera loads its surroundings.
+    static LOAD_ORDER_SCRATCH: std::cell::Cell<Vec<(i64, usize, Point2d)>> =
+        const { std::cell::Cell::new(Vec::new()) };
+
+    /// Set while [Layer::get_shallow] is computing a chunk.
+    static SHALLOW: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Marks the current thread as computing a chunk via [Layer::get_shallow]
+/// until dropped.
+struct Shallow(bool);
+
+impl Shallow {
+    fn enter() -> Self {
+        Self(SHALLOW.replace(true))
+    }
+}
+
+impl Drop for Shallow {
+    fn drop(&mut self) {
+        SHALLOW.set(self.0);
+    }
+}
+
+thread_local! {
+    /// Set while [Layer::inspect_deps] is computing a chunk.
+    static INSPECT: std::cell::RefCell<Option<Inspection>> = const { std::cell::RefCell::new(None) };
+}
+
+#[derive(Default)]
+struct Inspection {
+    /// How many dependency chunks are being computed, only chunks read
+    /// while this is zero are direct dependencies.
+    depth: usize,
+    snapshots: Vec<DependencySnapshot>,
+}
+
+/// Restores the previous [Inspection] when dropped.
+struct Inspect(Option<Inspection>);
+
+impl Drop for Inspect {
+    fn drop(&mut self) {
+        INSPECT.set(self.0.take());
+    }
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+    /// The chunks that are being computed on this thread, outermost first.
+    static COMPUTING: std::cell::RefCell<Vec<Computing>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// A chunk that is being computed, identified by its type, the dependencies of its layer and its index.
+#[cfg(debug_assertions)]
+struct Computing {
+    chunk: std::any::TypeId,
+    layer: usize,
+    index: Point2d,
+    name: fn() -> String,
+}
+
+#[cfg(debug_assertions)]
+impl Computing {
+    fn same(&self, other: &Self) -> bool {
+        (self.chunk, self.layer, self.index) == (other.chunk, other.layer, other.index)
+    }
+}
+
+#[cfg(debug_assertions)]
+impl std::fmt::Display for Computing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:?}", (self.name)(), self.index)
+    }
+}
+
+/// Removes the chunk from [COMPUTING] when dropped.
+#[cfg(debug_assertions)]
+struct ComputingGuard;
+
+#[cfg(debug_assertions)]
+impl ComputingGuard {
+    /// Panics if the chunk is already being computed, as computing it would need itself
+    /// and thus recurse forever.
+    #[track_caller]
+    fn enter<C: Chunk>(layer: &C::Dependencies, index: GridPoint<C>) -> Self {
+        let current = Computing {
+            chunk: std::any::TypeId::of::<C>(),
+            layer: std::ptr::from_ref(layer) as usize,
+            index: index.map(|i| i.0),
+            name: debug::short_type_name::<C>,
+        };
+        let cycle = COMPUTING.with_borrow_mut(|stack| {
+            let cycle = stack.iter().position(|c| c.same(&current)).map(|start| {
+                stack[start..]
+                    .iter()
+                    .chain([&current])
+                    .map(Computing::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            });
+            stack.push(current);
+            cycle
+        });
+        // Not panicking while `COMPUTING` is borrowed, as the guards of the outer chunks still need it.
+        if let Some(cycle) = cycle {
+            panic!("dependency cycle: {cycle}");
+        }
+        Self
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for ComputingGuard {
+    fn drop(&mut self) {
+        COMPUTING.with_borrow_mut(|stack| stack.pop());
+    }
+}
+
+/// Compute a chunk that was not found in the cache.
+/// All [ChunkGrid]s should go through this instead of calling [Chunk::compute] directly.
+///
+/// With debug assertions enabled, this detects dependency cycles, where computing
+/// a chunk needs the same chunk of the same layer again.
+#[track_caller]
+pub(crate) fn compute<C: Chunk>(layer: &C::Dependencies, index: GridPoint<C>) -> C {
+    #[cfg(debug_assertions)]
+    let _computing = ComputingGuard::enter::<C>(layer, index);
+    debug_assert!(
+        !SHALLOW.get(),
+        "dependency chunk {index:?} of {} was not loaded before `get_shallow`",
+        std::any::type_name::<C>()
+    );
+    C::compute(layer, index)
+}
+
+/// Same as [compute], but for [Layer::get_shallow], so all dependency chunks must be loaded already.
+#[track_caller]
+fn compute_shallow<C: Chunk>(layer: &C::Dependencies, index: GridPoint<C>) -> C {
+    #[cfg(debug_assertions)]
+    let _computing = ComputingGuard::enter::<C>(layer, index);
+    let _shallow = Shallow::enter();
+    C::compute(layer, index)
+}
+
+/// The result of [Layer::plan_load].
+pub struct LoadPlan<C> {
+    /// The chunks that are not cached and would need to be computed.
+    pub to_compute: Vec<GridPoint<C>>,
+    /// The chunks that are already cached.
+    pub cached: Vec<GridPoint<C>>,
+}
+
+impl<C> std::fmt::Debug for LoadPlan<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadPlan")
+            .field("to_compute", &self.to_compute)
+            .field("cached", &self.cached)
+            .finish()
+    }
+}
+
+impl<C> Clone for LoadPlan<C> {
+    fn clone(&self) -> Self {
+        Self {
+            to_compute: self.to_compute.clone(),
+            cached: self.cached.clone(),
+        }
+    }
+}
+
+impl<C> PartialEq for LoadPlan<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_compute == other.to_compute && self.cached == other.cached
+    }
+}
+
+impl<C> Eq for LoadPlan<C> {}
+
+/// Whether [Layer::get_with_freshness] computed a chunk or found it in the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// The chunk was not cached and has just been computed.
+    Created,
+    /// The chunk was already cached.
+    Cached,
+}
+
+/// Errors that the `try_` methods of [Layer] report instead of panicking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LayerError {
+    /// The world coordinates of a chunk are outside the range of [i64]
+    /// and the layer's [Chunk::COORD_MODE] is [CoordMode::Infinite].
+    CoordinateOverflow {
+        /// The type name of the chunk.
+        layer: &'static str,
+        /// The grid position of the chunk.
+        index: Point2d,
+    },
+    /// More chunks were requested at once than the [Chunk::Grid] of the layer can hold,
+    /// so some of them would have evicted others.
+    OverlapExceeded {
+        /// The type name of the chunk.
+        layer: &'static str,
+        /// The grid position of a chunk that would have evicted another requested one.
+        index: Point2d,
+        /// How many chunks were requested along each axis.
+        span: Point2d,
+    },
+}
+
+impl std::fmt::Display for LayerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayerError::CoordinateOverflow { layer, index } => write!(
+                f,
+                "world coordinates of chunk {index:?} of {layer} are outside the range of i64"
+            ),
+            LayerError::OverlapExceeded { layer, index, span } => write!(
+                f,
+                "chunk {index:?} of {layer} would evict another chunk when loading {span:?} chunks at once"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LayerError {}
+
 /// Chunks are rectangular regions of the same size that make up a layer in a grid shape.
+///
+/// Chunks are cloned whenever they are read from a layer, so they should be cheap to clone.
+/// Put larger data, or data that can't be cloned (e.g. `Box<dyn Trait>` for differently typed
+/// features), behind an [Arc](std::sync::Arc), like `Arc<[Box<dyn Feature>]>`, which is also [Default].
 pub trait Chunk: Sized + Default + Clone + 'static {
     /// Exponent of `2` of the cached area (in grid chunk numbers, not world coordinates).
     /// This is the area that should stay in memory at all times as it will get requested a lot.
+    /// The axes can be chosen independently, e.g. a wide but short area for side scrollers.
     const GRID_SIZE: Point2d<u8> = Point2d::splat(5);
 
     /// Internal `RollingGrid` overlap before the system drops old chunks. Basically scales the grid width/height by
     /// this number to allow moving across the grid width/height boundaries completely transparently.
     /// Increasing this number makes indexing the `RollingGrid` more expensive if there is a lot of overlap.
+    ///
+    /// This is the number of chunks that can share a grid cell, so it is not per axis.
+    /// Use [Chunk::GRID_SIZE] to tune the cached area per axis.
     const GRID_OVERLAP: u8 = 3;
 
     /// Data structure that stores the layer. Usually `Arc<Self>`,
@@ -245,17 +1042,56 @@ pub trait Chunk: Sized + Default + Clone + 'static {
     /// they can get stored directly without the `Arc` indirection.
     type LayerStore<T>: Borrow<T> + From<T>;
 
+    /// Data structure that caches the chunks of the layer. Usually [RollingGrid],
+    /// but layers that only get accessed sparsely at far-apart positions can
+    /// use [SparseGrid] to avoid evicting chunks that are still needed.
+    type Grid: ChunkGrid<Self>;
+
     /// Width and height of the chunk (in powers of two);
     const SIZE: Point2d<u8> = Point2d::splat(8);
 
-    /// Compute a chunk from its dependencies
-    fn compute(layer: &Self::Dependencies, index: GridPoint<Self>) -> Self;
+    /// How the coordinate computations of this layer (e.g. [ChunkExt::bounds]) behave
+    /// at the limits of [i64] world coordinates.
+    const COORD_MODE: CoordMode = CoordMode::Infinite;
+
+    /// The world position of the `min` corner of the chunk at grid index `(0, 0)`.
+    ///
+    /// All chunk index math is relative to this origin, so a game centered far away from `(0, 0)`
+    /// can keep its chunk indices (and thus the seeds of its chunks) small. Layers with the same
+    /// origin-relative indices generate the same content, shifted by the difference of their origins.
+    const ORIGIN: Point2d = Point2d::splat(0);
+
+    /// Compute a chunk from its dependencies via [Chunk::compute_in_bounds].
+    fn compute(layer: &Self::Dependencies, index: GridPoint<Self>) -> Self {
+        Self::compute_in_bounds(layer, index, Self::bounds(index))
+    }
+
+    /// Compute a chunk from its dependencies, getting the world [Bounds]
+    /// of the chunk (as returned by [ChunkExt::bounds]) passed in.
+    fn compute_in_bounds(
+        layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self;
 
     /// Clear all information that [compute] would have computed
-    fn clear(layer: &Self::Dependencies, index: GridPoint<Self>);
+    ///
+    /// Only needs to be implemented if the [Chunk::Dependencies] contain layers
+    /// (see [Dependencies::HAS_LAYERS]), otherwise there is nothing to clear.
+    fn clear(layer: &Self::Dependencies, index: GridPoint<Self>) {
+        const {
+            assert!(
+                !Self::Dependencies::HAS_LAYERS,
+                "chunks with layer dependencies must implement `Chunk::clear`"
+            )
+        };
+        let _ = (layer, index);
+    }
 
     /// Called on [Drop], [Chunk::clear] or when
     /// the chunk gets overwritten because it was too old.
+    /// Called exactly once for every chunk that was cached. Copies of the chunk that
+    /// were handed out (e.g. by [Layer::get]) stay valid afterwards.
     fn on_drop(&self, _layer: &Self::Dependencies, _index: GridPoint<Self>) {}
 
     /// The actual dependencies. Usually a struct with fields of `Layer<T>` type, but
@@ -266,34 +1102,126 @@ pub trait Chunk: Sized + Default + Clone + 'static {
 
 /// Various helpers that a [Chunk] frequently needs
 pub trait ChunkExt: Chunk {
-    /// Get the bounds for the chunk at the given index
+    /// Get the bounds for the chunk at the given index.
+    ///
+    /// The chunk covers the half-open area `min..max` on both axes, so positions at
+    /// `max.x` or `max.y` already belong to the neighboring chunk. This is consistent
+    /// with [ChunkExt::pos_to_grid] and [Bounds::sample], so points generated by a chunk
+    /// will never be part of another chunk.
+    #[track_caller]
     fn bounds(index: GridPoint<Self>) -> Bounds {
         let size = Self::SIZE.map(|i| 1 << i);
-        let min = index.map(|i| i.0) * size;
+        let min = index
+            .map(|i| i.0)
+            .mul_with(size, Self::COORD_MODE)
+            .add_with(Self::ORIGIN, Self::COORD_MODE);
+        Bounds {
+            min,
+            max: min.add_with(size, Self::COORD_MODE),
+        }
+    }
+
+    /// Same as [ChunkExt::bounds], but reports an error instead of panicking or wrapping if
+    /// the bounds are outside the range of [i64] and [Chunk::COORD_MODE] is [CoordMode::Infinite].
+    fn checked_bounds(index: GridPoint<Self>) -> Result<Bounds, LayerError> {
+        if Self::COORD_MODE != CoordMode::Infinite {
+            return Ok(Self::bounds(index));
+        }
+        let size = Self::SIZE.map(|i| 1_i64 << i);
+        let checked = |index: i64, size: i64, origin: i64| {
+            let min = index.checked_mul(size)?.checked_add(origin)?;
+            Some((min, min.checked_add(size)?))
+        };
+        match (
+            checked(index.x.0, size.x, Self::ORIGIN.x),
+            checked(index.y.0, size.y, Self::ORIGIN.y),
+        ) {
+            (Some((min_x, max_x)), Some((min_y, max_y))) => Ok(Bounds {
+                min: Point2d::new(min_x, min_y),
+                max: Point2d::new(max_x, max_y),
+            }),
+            _ => Err(LayerError::CoordinateOverflow {
+                layer: std::any::type_name::<Self>(),
+                index: index.map(|i| i.0),
+            }),
+        }
+    }
+
+    /// Get the grids that are touched by the given bounds, i.e. the chunks
+    /// containing any of their points. Empty if the bounds are [empty](Bounds::is_empty).
+    ///
+    /// As `max` is not part of the bounds, a `max` that lies exactly on a
+    /// chunk border does not include the chunk starting at that border, so
+    /// `bounds_to_grid(bounds(index))` is just `index`.
+    fn bounds_to_grid(bounds: Bounds) -> GridBounds<Self> {
+        let min = Self::pos_to_grid(bounds.min);
+        if bounds.is_empty() {
+            return Bounds { min, max: min };
+        }
         Bounds {
             min,
-            max: min + size,
+            max: Self::pos_to_grid(bounds.max - Point2d::splat(1))
+                + Point2d::splat(GridIndex::from_raw(1)),
         }
     }
 
-    /// Get the grids that are touched by the given bounds.
-    fn bounds_to_grid(bounds: Bounds) -> Bounds<GridIndex<Self>> {
-        bounds.map(Self::pos_to_grid)
+    /// Get the minimal grid bounds whose chunks together cover the given area.
+    ///
+    /// Same as [ChunkExt::bounds_to_grid], but contains at least the chunk of `min`,
+    /// even if the bounds are [empty](Bounds::is_empty).
+    fn covering_grid_bounds(bounds: Bounds) -> GridBounds<Self> {
+        let Bounds { min, max } = Self::bounds_to_grid(bounds);
+        let first = min + Point2d::splat(GridIndex::from_raw(1));
+        Bounds {
+            min,
+            max: Point2d::new(max.x.max(first.x), max.y.max(first.y)),
+        }
     }
 
-    /// Get the grid the position is in
+    /// Get the grid the position is in. Chunk borders belong to the chunk
+    /// that has them as its `min` (see [ChunkExt::bounds]).
     fn pos_to_grid(point: Point2d) -> GridPoint<Self> {
-        RollingGrid::<Self>::pos_to_grid_pos(point)
+        RollingGrid::<Self>::pos_to_grid_pos(point.sub_with(Self::ORIGIN, Self::COORD_MODE))
+    }
+
+    /// Get the position of a point relative to the chunk it is in, i.e. the offset
+    /// from the `min` of that chunk's [bounds](ChunkExt::bounds). Always in `0..(1 << SIZE)`.
+    fn pos_within_chunk(point: Point2d) -> Point2d {
+        let mask = Self::SIZE.map(|i| (1 << i) - 1);
+        let point = point.sub_with(Self::ORIGIN, Self::COORD_MODE);
+        Point2d::new(point.x & mask.x, point.y & mask.y)
+    }
+
+    /// Generate a random point within the chunk at the given index.
+    ///
+    /// The point is sampled as an offset in `0..(1 << SIZE)` from the chunk's origin,
+    /// so the result only depends on the index and [Chunk::SIZE], not on how the chunk's
+    /// [bounds](ChunkExt::bounds) are represented.
+    fn sample_point<R: RngCore + ?Sized>(index: GridPoint<Self>, rng: &mut R) -> Point2d {
+        let size = Self::SIZE.map(|i| 1 << i);
+        let local = Bounds {
+            min: Point2d::splat(0),
+            max: size,
+        }
+        .sample(rng);
+        Self::bounds(index).min + local
     }
 
     /// Pad by a chunk size to make sure we see effects from the neighboring chunks
+    #[track_caller]
     fn vision_range(bounds: Bounds) -> Bounds {
-        bounds.pad(Self::SIZE.map(|i| 1 << i))
+        bounds.pad_with(Self::SIZE.map(|i| 1 << i), Self::COORD_MODE)
     }
 
     /// Get 3x3 grid points around a central one
+    #[track_caller]
     fn moore_neighborhood(index: GridPoint<Self>) -> [[GridPoint<Self>; 3]; 3] {
-        let p = |x, y| index + GridPoint::new(GridIndex::from_raw(x), GridIndex::from_raw(y));
+        let p = |x, y| {
+            index
+                .map(|i| i.0)
+                .add_with(Point2d::new(x, y), Self::COORD_MODE)
+                .map(GridIndex::from_raw)
+        };
         [
             [p(-1, -1), p(0, -1), p(1, -1)],
             [p(-1, 0), p(0, 0), p(1, 0)],
@@ -304,5 +1232,9 @@ pub trait ChunkExt: Chunk {
 
 impl<T: Chunk> ChunkExt for T {}
 
+mod async_chunk;
 mod rolling_grid;
+mod sparse_grid;
+mod sync_rolling_grid;
 pub mod vec2;
+mod vision_range;
@@ -0,0 +1,63 @@
+//! Helpers for writing generated chunks to persistent storage without
+//! slowing down their generation.
+
+use std::{
+    sync::mpsc::{SyncSender, sync_channel},
+    thread::JoinHandle,
+};
+
+/// Hands items (e.g. serialized chunks) to a background thread that writes them,
+/// so that [Chunk::compute](crate::Chunk::compute) does not have to wait for the disk.
+///
+/// The queue has a fixed capacity. Once it is full, [BackgroundWriter::enqueue] waits for
+/// the writer thread to catch up, so memory use stays bounded if generation is faster than the disk.
+/// Reading has to stay synchronous, as a chunk is needed right away when it is requested.
+pub struct BackgroundWriter<T> {
+    sender: Option<SyncSender<T>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> BackgroundWriter<T> {
+    /// Spawn a thread that calls `write` for every enqueued item, in order.
+    /// At most `capacity` items wait to be written at any time.
+    pub fn new(capacity: usize, mut write: impl FnMut(T) + Send + 'static) -> Self {
+        let (sender, receiver) = sync_channel(capacity);
+        let thread = std::thread::spawn(move || {
+            for item in receiver {
+                write(item)
+            }
+        });
+        Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    /// Queue an item to be written. Only waits if the queue is full.
+    #[track_caller]
+    pub fn enqueue(&self, item: T) {
+        if self.sender.as_ref().unwrap().send(item).is_err() {
+            panic!("background writer thread panicked")
+        }
+    }
+
+    /// Wait until all queued items have been written.
+    /// Panics if the `write` callback panicked.
+    pub fn finish(mut self) {
+        // Closing the channel ends the loop in the thread.
+        drop(self.sender.take());
+        if let Err(err) = self.thread.take().unwrap().join() {
+            std::panic::resume_unwind(err)
+        }
+    }
+}
+
+impl<T> Drop for BackgroundWriter<T> {
+    /// Waits until all queued items have been written.
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Computing chunks on a background thread before they are needed.
+
+use std::{
+    sync::{
+        Arc,
+        mpsc::{Sender, channel},
+    },
+    thread::JoinHandle,
+};
+
+use crate::{Chunk, Layer, vec2::Bounds};
+
+enum Request {
+    Load(Bounds),
+    /// Reply once all earlier requests are done.
+    Flush(Sender<()>),
+}
+
+/// Computes chunks of a shared layer on a background thread, e.g. the area a fast moving
+/// camera will reach in the next frames (extrapolated from its velocity), so they are
+/// already cached once the render thread requests them.
+///
+/// The layer is only accessed through a shared reference, so its [Chunk::Grid] needs to
+/// synchronize the worker with the other threads, e.g. a [SyncRollingGrid](crate::SyncRollingGrid)
+/// with a [Chunk::LayerStore] of `Arc<T>`. The same is required from all dependency layers.
+/// If another thread requests a chunk while the worker is still computing it, both compute it.
+/// As chunks only depend on their dependencies, both get the same result.
+pub struct Prefetcher<C: Chunk> {
+    layer: Arc<Layer<C>>,
+    sender: Option<Sender<Request>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<C: Chunk> Prefetcher<C>
+where
+    Layer<C>: Send + Sync,
+{
+    /// Spawn a thread that loads the chunks requested via [Prefetcher::prefetch] into `layer`.
+    pub fn new(layer: Arc<Layer<C>>) -> Self {
+        let (sender, receiver) = channel();
+        let thread = std::thread::spawn({
+            let layer = layer.clone();
+            move || {
+                for request in receiver {
+                    match request {
+                        Request::Load(bounds) => layer.ensure_loaded_in_bounds(bounds),
+                        Request::Flush(done) => {
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            layer,
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    /// The layer the chunks are loaded into.
+    pub fn layer(&self) -> &Arc<Layer<C>> {
+        &self.layer
+    }
+
+    /// Queue loading all chunks touching `bounds` (in world coordinates), see
+    /// [Layer::ensure_loaded_in_bounds]. Requests are handled in order, without blocking the caller.
+    #[track_caller]
+    pub fn prefetch(&self, bounds: Bounds) {
+        self.send(Request::Load(bounds))
+    }
+
+    /// Wait until all queued requests have been handled.
+    #[track_caller]
+    pub fn wait(&self) {
+        let (sender, receiver) = channel();
+        self.send(Request::Flush(sender));
+        if receiver.recv().is_err() {
+            panic!("prefetch thread panicked")
+        }
+    }
+
+    #[track_caller]
+    fn send(&self, request: Request) {
+        if self.sender.as_ref().unwrap().send(request).is_err() {
+            panic!("prefetch thread panicked")
+        }
+    }
+}
+
+impl<C: Chunk> Drop for Prefetcher<C> {
+    /// Waits until all queued requests have been handled.
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
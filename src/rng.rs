@@ -0,0 +1,208 @@
+//! Deterministic random number generation for chunks.
+
+use rand::prelude::*;
+
+use crate::{Seed, vec2::Num, vec2::Point2d};
+
+type RawSeed = <SmallRng as SeedableRng>::Seed;
+
+/// The base random seed of a chunk, from which any number of
+/// independent random number generators can be created.
+///
+/// Useful if a chunk needs to make several independent random decisions
+/// (e.g. positions and attributes of things), where changing how many of
+/// one kind are generated should not affect the others.
+///
+/// The seed is a fixed hash of the chunk position, salt and world seed, so neighbouring chunks get
+/// uncorrelated random numbers. Note that [SmallRng] itself uses a different algorithm on
+/// 32 bit platforms, so the generated numbers are only the same across 64 bit platforms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkRng {
+    seed: RawSeed,
+}
+
+impl ChunkRng {
+    /// Create the base seed for a chunk position, its layer's `SALT` and the world seed.
+    pub fn new<const SALT: u64, T: Num>(index: Point2d<T>, seed: Seed) -> Self {
+        Self::with_salt(index, SALT, seed)
+    }
+
+    /// Same as [ChunkRng::new], but with a salt chosen at runtime (e.g. from a [LayerConfig](crate::generic_layers::LayerConfig)).
+    pub fn with_salt<T: Num>(index: Point2d<T>, salt: u64, seed: Seed) -> Self {
+        Self {
+            seed: mix([index.x.as_u64(), index.y.as_u64(), salt, seed.0]),
+        }
+    }
+
+    /// The random number generator for the `id`th stream of this chunk.
+    /// The same `id` always produces the same sequence.
+    pub fn stream(&self, id: u64) -> SmallRng {
+        let mut seed = mix([id]);
+        xor(&mut seed, &self.seed);
+        SmallRng::from_seed(seed)
+    }
+
+    /// A random number generator directly seeded with the base seed.
+    pub(crate) fn base(&self) -> SmallRng {
+        SmallRng::from_seed(self.seed)
+    }
+
+    /// A random number generator of any type directly seeded with the base seed.
+    /// For [SmallRng] this is the same generator [rng_for_point](crate::generic_layers::rng_for_point)
+    /// returns. Seeds shorter than 32 bytes use its beginning, longer ones are
+    /// filled up with the output of that [SmallRng].
+    pub fn base_as<R: SeedableRng>(&self) -> R {
+        let mut seed = R::Seed::default();
+        let bytes = seed.as_mut();
+        let n = bytes.len().min(self.seed.len());
+        bytes[..n].copy_from_slice(&self.seed[..n]);
+        self.base().fill_bytes(&mut bytes[n..]);
+        R::from_seed(seed)
+    }
+}
+
+/// Shuffle `slice` into a random order determined by `rng` (Fisher-Yates).
+///
+/// Only uses `u64`s from `rng`, never `usize`s, so the order is the same on all platforms
+/// for the same random number generator output.
+pub fn shuffle_in_place<T, R: RngCore + ?Sized>(slice: &mut [T], rng: &mut R) {
+    for i in (1..slice.len()).rev() {
+        let j = rng.random_range(0..=i as u64);
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "j is at most i, which is a usize"
+        )]
+        slice.swap(i, j as usize);
+    }
+}
+
+/// The numbers `0..n` in a random order determined by `seed`, e.g. to process the candidate
+/// points of a chunk in an order that does not depend on their position.
+///
+/// In contrast to [SmallRng], whose algorithm differs between platforms, the generator used here is
+/// fixed, so the same seed gives the same order everywhere.
+pub fn shuffled_indices(n: usize, seed: Seed) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..n).collect();
+    shuffle_in_place(&mut indices, &mut SplitMix64(seed.0));
+    indices
+}
+
+/// A minimal random number generator with a fixed algorithm, see <https://prng.di.unimi.it/splitmix64.c>.
+struct SplitMix64(u64);
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+/// Hash the values into a seed with a fixed algorithm, so it's the same on all platforms.
+/// The order of the values matters, and equal values don't cancel each other out,
+/// so e.g. the chunks at `(1, 2)`, `(2, 1)` and `(3, 3)` all get unrelated seeds.
+fn mix<const N: usize>(values: [u64; N]) -> RawSeed {
+    let mut state = SplitMix64(0);
+    for value in values {
+        state = SplitMix64(state.next_u64() ^ value);
+    }
+    let mut seed = RawSeed::default();
+    state.fill_bytes(&mut seed);
+    seed
+}
+
+fn xor(seed: &mut RawSeed, other: &RawSeed) {
+    for (seed, other) in seed.iter_mut().zip(other) {
+        *seed ^= *other;
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn streams_are_reproducible() {
+    let rng = ChunkRng::new::<0, i64>(Point2d::new(5, -3), Seed(42));
+    let a: Vec<u64> = rng.stream(1).random_iter().take(100).collect();
+    let b: Vec<u64> = rng.stream(1).random_iter().take(100).collect();
+    assert_eq!(a, b);
+}
+
+#[cfg(test)]
+#[test]
+fn streams_are_independent() {
+    let rng = ChunkRng::new::<0, i64>(Point2d::new(5, -3), Seed(42));
+    let a: Vec<u64> = rng.stream(1).random_iter().take(1000).collect();
+    let b: Vec<u64> = rng.stream(2).random_iter().take(1000).collect();
+    assert!(a.iter().zip(&b).all(|(a, b)| a != b));
+    // Uncorrelated streams agree on about half of their bits.
+    let same_bits: u32 = a.iter().zip(&b).map(|(a, b)| (!(a ^ b)).count_ones()).sum();
+    let ratio = f64::from(same_bits) / 64_000.;
+    assert!((0.48..0.52).contains(&ratio), "{ratio}");
+}
+
+#[cfg(test)]
+#[test]
+fn neighbours_are_independent() {
+    let first_draw = |x: i64, y: i64| {
+        ChunkRng::new::<0, i64>(Point2d::new(x, y), Seed(42))
+            .base()
+            .next_u64()
+    };
+    let mut same_bits = 0;
+    for i in 0..1000 {
+        let a = first_draw(i, 0);
+        let b = first_draw(i + 1, 0);
+        let c = first_draw(i, 1);
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        same_bits += (!(a ^ b)).count_ones() + (!(a ^ c)).count_ones();
+        // Swapped and equal coordinates don't collide.
+        assert_ne!(first_draw(i, i + 1), first_draw(i + 1, i));
+        assert_ne!(first_draw(i, i), first_draw(i + 1, i + 1));
+    }
+    let ratio = f64::from(same_bits) / 128_000.;
+    assert!((0.48..0.52).contains(&ratio), "{ratio}");
+}
+
+#[cfg(test)]
+#[test]
+fn seed_is_pinned() {
+    // Any change to the hash changes all generated worlds.
+    let rng = ChunkRng::with_salt(Point2d::new(5_i64, -3), 7, Seed(42));
+    assert_eq!(
+        u64::from_le_bytes(rng.seed[..8].try_into().unwrap()),
+        0x2fd9_d875_2e24_bdad
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn shuffle() {
+    // Pinned, so any platform or dependency specific behavior shows up as failure.
+    assert_eq!(SplitMix64(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+    assert_eq!(
+        shuffled_indices(10, Seed(42)),
+        [8, 3, 6, 5, 4, 0, 9, 2, 1, 7]
+    );
+    assert_ne!(
+        shuffled_indices(10, Seed(42)),
+        shuffled_indices(10, Seed(43))
+    );
+    let mut sorted = shuffled_indices(100, Seed(7));
+    sorted.sort();
+    assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+    assert_eq!(shuffled_indices(0, Seed(1)), []);
+    assert_eq!(shuffled_indices(1, Seed(1)), [0]);
+}
@@ -1,38 +1,163 @@
 use crate::{
-    Chunk,
-    vec2::{Abs, Num, Point2d},
+    Chunk, ChunkExt as _, Dependencies as _, Freshness, VersionCache,
+    vec2::{Abs, Bounds, Num, Point2d},
 };
 use std::{
     cell::{Cell, RefCell},
     hash::Hash,
     marker::PhantomData,
     ops::{Div, DivAssign, Neg},
+    sync::atomic::{AtomicU64, Ordering},
 };
 
 /// The x and y positions of a chunk in the number of chunks, not in world coordinates.
 pub type GridPoint<C> = crate::vec2::Point2d<GridIndex<C>>;
 
+/// A rectangle of chunks, in the number of chunks, not in world coordinates.
+/// Like all [Bounds](crate::Bounds), it is half-open: it includes `min`, but not `max`.
+pub type GridBounds<C> = crate::vec2::Bounds<GridIndex<C>>;
+
+/// The storage backend that caches the chunks of a [Layer](crate::Layer).
+///
+/// Chosen per [Chunk] type via [Chunk::Grid]. Usually [RollingGrid], which is
+/// optimized for dense access around a moving point of interest. Layers that see
+/// only a few far-apart accesses can use [SparseGrid](crate::SparseGrid) instead.
+pub trait ChunkGrid<C: Chunk>: Default + 'static {
+    /// If the position is already occupied with a chunk, fetch it.
+    /// Otherwise generate the chunk.
+    fn get(&self, pos: GridPoint<C>, layer: &C::Dependencies) -> (C, Freshness);
+
+    /// Manually (without calling `compute`) set a chunk in the cache.
+    fn set(&self, layer: &C::Dependencies, pos: GridPoint<C>, val: C);
+
+    /// Whether the chunk at the given position is currently cached.
+    fn contains(&self, pos: GridPoint<C>) -> bool;
+
+    /// Fetch the chunk at the given position if it is cached, without computing it
+    /// or otherwise modifying the cache (e.g. LRU timestamps).
+    fn peek(&self, pos: GridPoint<C>) -> Option<C>;
+
+    /// Remove the chunk at the given position from the cache and
+    /// clear the dependencies it was computed from.
+    fn clear(&self, pos: GridPoint<C>, layer: &C::Dependencies);
+
+    /// Iterate over all chunks that are currently cached.
+    fn iter_all_loaded(&self) -> impl Iterator<Item = (GridPoint<C>, C)> + '_;
+
+    /// Invoke [Chunk::on_drop] for all cached chunks.
+    fn drop(&self, layer: &C::Dependencies);
+
+    /// The first chunk of `bounds` (in [Bounds::iter] order) that would evict another chunk of
+    /// `bounds` when loading all of them, because the grid can't hold all of them at once.
+    ///
+    /// Defaults to `None`, for grids without a capacity limit.
+    fn first_overflowing(&self, bounds: GridBounds<C>) -> Option<GridPoint<C>> {
+        let _ = bounds;
+        None
+    }
+}
+
+/// A fixed size cache of chunks that evicts the least recently used chunks
+/// once more chunks map to the same grid cell than [Chunk::GRID_OVERLAP] allows.
 // TODO: avoid the box when generic const exprs allow for it
 // The Layer that contains it will already get put into an `Arc`
-pub(crate) struct RollingGrid<C: Chunk> {
+pub struct RollingGrid<C: Chunk> {
     /// The inner slice contains to `L::OVERLAP` entries,
     /// some of which are `None` if they have nevef been used
     /// so far.
     grid: Box<[Box<[ActiveCell<C>]>]>,
+    /// [Chunk::GRID_SIZE], unless chosen at runtime via [RollingGrid::with_size].
+    size: Point2d<u8>,
     time: Cell<u64>,
+    stats: GridCounters,
+    deps_version: VersionCache,
+}
+
+/// How a [RollingGrid] or [SyncRollingGrid](crate::SyncRollingGrid) was used since it was
+/// created or since its stats were last reset, see [RollingGrid::stats].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GridStats {
+    /// Chunks that were requested and had to be computed.
+    pub computes: u64,
+    /// Chunks that were requested and found in the cache.
+    pub cache_hits: u64,
+    /// Cached chunks that were removed to make room for other chunks.
+    /// Explicitly cleared chunks are not counted.
+    pub evictions: u64,
+    /// How many chunks are currently cached, out of the capacity of
+    /// `2^size.x * 2^size.y * overlap` chunks (see [RollingGrid::with_size]).
+    /// Not reset by resetting the stats.
+    pub occupied_slots: usize,
+}
+
+/// The counters of [GridStats]. Atomic, so grids shared between threads can count, too.
+#[derive(Default)]
+pub(crate) struct GridCounters {
+    computes: AtomicU64,
+    cache_hits: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl GridCounters {
+    pub(crate) fn compute(&self) {
+        self.computes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn eviction(&self) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self, occupied_slots: usize) -> GridStats {
+        GridStats {
+            computes: self.computes.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            occupied_slots,
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        for counter in [&self.computes, &self.cache_hits, &self.evictions] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
 }
 
 impl<C: Chunk> Default for RollingGrid<C> {
     fn default() -> Self {
+        const { assert!(C::GRID_OVERLAP > 0, "GRID_OVERLAP must not be zero") };
+        const { assert!((C::GRID_SIZE.x as u32) + (C::GRID_SIZE.y as u32) < usize::BITS) };
+        Self::with_size(C::GRID_SIZE, C::GRID_OVERLAP)
+    }
+}
+
+impl<C: Chunk> RollingGrid<C> {
+    /// A grid with a size and overlap chosen at runtime instead of [Chunk::GRID_SIZE] and
+    /// [Chunk::GRID_OVERLAP], e.g. depending on the view distance picked by the player.
+    /// Use it via [Layer::with_grid](crate::Layer::with_grid).
+    #[track_caller]
+    pub fn with_size(size: Point2d<u8>, overlap: u8) -> Self {
+        assert!(overlap > 0, "overlap must not be zero");
+        assert!(
+            u32::from(size.x) + u32::from(size.y) < usize::BITS,
+            "grid size {size:?} is too large"
+        );
         Self {
             grid: std::iter::repeat_with(|| {
                 std::iter::repeat_with(Default::default)
-                    .take(C::GRID_OVERLAP.into())
+                    .take(overlap.into())
                     .collect()
             })
-            .take((1 << C::GRID_SIZE.x) << C::GRID_SIZE.y)
+            .take((1 << size.x) << size.y)
             .collect(),
+            size,
             time: Cell::new(1),
+            stats: Default::default(),
+            deps_version: Default::default(),
         }
     }
 }
@@ -203,27 +328,129 @@ impl<C> DivAssign<i64> for GridIndex<C> {
     }
 }
 
+impl<C> GridPoint<C> {
+    /// Create a [GridPoint] directly from raw chunk indices.
+    /// Shorthand for `Point2d::new(x, y).map(GridIndex::from_raw)`, see also [point!](crate::point).
+    pub const fn from_raw(x: i64, y: i64) -> Self {
+        Self {
+            x: GridIndex::from_raw(x),
+            y: GridIndex::from_raw(y),
+        }
+    }
+}
+
+impl<C> GridPoint<C> {
+    /// A color for debug views that is always the same for the same position,
+    /// but differs noticeably between neighboring positions.
+    pub fn debug_color(&self) -> [u8; 3] {
+        let hash = crate::golden::content_hash(&(self.x.0, self.y.0));
+        // Pick a hue and convert it to a fully saturated RGB color.
+        let [rise, sector, ..] = hash.to_le_bytes();
+        let fall = 255 - rise;
+        match sector % 6 {
+            0 => [255, rise, 0],
+            1 => [fall, 255, 0],
+            2 => [0, 255, rise],
+            3 => [0, fall, 255],
+            4 => [rise, 0, 255],
+            _ => [255, 0, fall],
+        }
+    }
+}
+
+/// Shows both the grid indices and the world coordinates
+/// (as in [ChunkExt::bounds](crate::ChunkExt::bounds)) of the chunk.
+impl<C: Chunk> std::fmt::Display for GridPoint<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Bounds { min, max } = C::bounds(*self);
+        write!(
+            f,
+            "({}, {}) at ({}, {})..({}, {})",
+            self.x.0, self.y.0, min.x, min.y, max.x, max.y
+        )
+    }
+}
+
+impl<C> GridBounds<C> {
+    /// The chunk indices as `[min.x, min.y, max.x, max.y]`, e.g. for binary headers,
+    /// logging or FFI, without needing the `serde` feature.
+    pub const fn to_array(self) -> [i64; 4] {
+        [self.min.x.0, self.min.y.0, self.max.x.0, self.max.y.0]
+    }
+
+    /// The number of chunk indices [Bounds::iter] yields, e.g. for preallocating
+    /// per-chunk buffers. Zero if the bounds are [empty](Bounds::is_empty).
+    pub fn index_count(&self) -> usize {
+        let axis = |min: GridIndex<C>, max: GridIndex<C>| {
+            let len = (i128::from(max.0) - i128::from(min.0)).max(0);
+            usize::try_from(len).unwrap_or(usize::MAX)
+        };
+        axis(self.min.x, self.max.x).saturating_mul(axis(self.min.y, self.max.y))
+    }
+
+    /// The inverse of [GridBounds::to_array].
+    pub const fn from_array([min_x, min_y, max_x, max_y]: [i64; 4]) -> Self {
+        Self {
+            min: GridPoint::new(GridIndex::from_raw(min_x), GridIndex::from_raw(min_y)),
+            max: GridPoint::new(GridIndex::from_raw(max_x), GridIndex::from_raw(max_y)),
+        }
+    }
+}
+
 impl<C: Chunk> GridPoint<C> {
     /// When two [Chunk]s have the same size, all their coordinates are trivially
     /// the same and we can convert them with just a compile-time check.
+    ///
+    /// Prefer [GridPoint::same_index], which is the same operation.
     pub fn into_same_chunk_size<D: Chunk>(self) -> GridPoint<D> {
-        const { assert!(C::SIZE.x == D::SIZE.x && C::SIZE.y == D::SIZE.y) };
+        self.same_index()
+    }
+
+    /// The index of the chunk of a `D` layer that covers exactly the same area as this chunk.
+    /// This is the identity, as it only compiles if `C` and `D` have the same [Chunk::SIZE]
+    /// and [Chunk::ORIGIN].
+    /// Use [GridPoint::covering_indices] for chunks of different sizes.
+    pub fn same_index<D: Chunk>(self) -> GridPoint<D> {
+        const {
+            assert!(C::SIZE.x == D::SIZE.x && C::SIZE.y == D::SIZE.y);
+            assert!(C::ORIGIN.x == D::ORIGIN.x && C::ORIGIN.y == D::ORIGIN.y);
+        };
         GridPoint {
             x: GridIndex::from_raw(self.x.0),
             y: GridIndex::from_raw(self.y.0),
         }
     }
+
+    /// The indices of all chunks of a `D` layer that overlap with this chunk.
+    /// If `D` chunks are larger, this is the single chunk containing this one,
+    /// if they are smaller, these are all the chunks within this one.
+    pub fn covering_indices<D: Chunk>(self) -> impl Iterator<Item = GridPoint<D>> {
+        D::covering_grid_bounds(C::bounds(self)).iter()
+    }
 }
 
 struct ActiveCell<C: Chunk> {
     pos: Cell<GridPoint<C>>,
+    /// The world coordinates of `pos`, so hot loops don't have to recompute them.
+    bounds: Cell<Bounds>,
     chunk: RefCell<C>,
     last_access: Cell<u64>,
+    /// The [Dependencies::version](crate::Dependencies::version) the chunk was computed with.
+    version: Cell<u64>,
 }
 
 impl<C: Chunk> ActiveCell<C> {
     fn drop(&self, layer: &C::Dependencies) {
-        self.chunk.borrow().on_drop(layer, self.pos.get())
+        // Unused and cleared cells only contain a default chunk.
+        if self.last_access.get() != 0 {
+            self.chunk.borrow().on_drop(layer, self.pos.get())
+        }
+    }
+
+    /// Move the cell to a new position, returning the previous one.
+    fn replace_pos(&self, pos: GridPoint<C>) -> GridPoint<C> {
+        self.bounds.set(C::bounds(pos));
+        self.pos.replace(pos)
     }
 }
 
@@ -231,14 +458,21 @@ impl<C: Chunk> Default for ActiveCell<C> {
     fn default() -> Self {
         Self {
             pos: GridPoint::splat(GridIndex::from_raw(i64::MIN)).into(),
+            bounds: Default::default(),
             chunk: Default::default(),
             last_access: Cell::new(0),
+            version: Cell::new(0),
         }
     }
 }
 
-impl<C: Chunk> RollingGrid<C> {
-    pub fn drop(&self, layer: &C::Dependencies) {
+impl<C: Chunk> ChunkGrid<C> for RollingGrid<C> {
+    fn first_overflowing(&self, bounds: GridBounds<C>) -> Option<GridPoint<C>> {
+        let overlap = self.grid.first().map_or(0, |cell| cell.len());
+        Self::first_overflowing_in(bounds, self.size, overlap)
+    }
+
+    fn drop(&self, layer: &C::Dependencies) {
         for cell in self.grid.iter().flatten() {
             cell.drop(layer)
         }
@@ -247,44 +481,51 @@ impl<C: Chunk> RollingGrid<C> {
     #[track_caller]
     /// If the position is already occupied with a block, fetch it and update the LRU timestamp for that block.
     /// Otherwise generate the block.
-    pub fn get(&self, pos: GridPoint<C>, layer: &C::Dependencies) -> C {
+    fn get(&self, pos: GridPoint<C>, layer: &C::Dependencies) -> (C, Freshness) {
         let now = self.time.get();
         self.time.set(now.checked_add(1).unwrap());
-        let free = match self.find_free_or_entry(pos, now) {
-            Ok(value) => value,
-            Err(p) => return p.chunk.borrow().clone(),
+        let version = self.deps_version.get(|| layer.version());
+        let (free, stale) = match self.find_free_or_entry(pos, now) {
+            Ok(value) => (value, false),
+            Err(p) if p.version.get() == version => {
+                self.stats.cache_hit();
+                return (p.chunk.borrow().clone(), Freshness::Cached);
+            }
+            // Computed before a dependency was bumped, so recompute it in place.
+            Err(p) => (p, true),
         };
-        let chunk = C::compute(layer, pos);
-        let prev_pos = free.pos.replace(pos);
+        self.stats.compute();
+        let chunk = crate::compute(layer, pos);
+        let occupied = free.last_access.get() != 0;
+        if occupied && !stale {
+            self.stats.eviction();
+        }
+        let prev_pos = free.replace_pos(pos);
         let prev = free.chunk.replace(chunk.clone());
-        prev.on_drop(layer, prev_pos);
+        free.version.set(version);
+        if occupied {
+            prev.on_drop(layer, prev_pos);
+        }
         free.last_access.set(now);
-        chunk
+        (chunk, Freshness::Created)
     }
 
-    fn find_free_or_entry(
-        &self,
-        pos: Point2d<GridIndex<C>>,
-        now: u64,
-    ) -> Result<&ActiveCell<C>, &ActiveCell<C>> {
-        let (mut free, mut rest) = self.access(pos).split_first().unwrap();
-        while let Some((p, r)) = rest.split_first() {
-            rest = r;
-            if p.last_access.get() == 0 {
-            } else if p.pos.get() == pos {
-                p.last_access.set(now);
-                return Err(p);
-            } else if free.last_access < p.last_access {
-                continue;
-            }
-            free = p;
-        }
-        Ok(free)
+    fn contains(&self, pos: GridPoint<C>) -> bool {
+        self.access(pos)
+            .iter()
+            .any(|cell| cell.last_access.get() != 0 && cell.pos.get() == pos)
+    }
+
+    fn peek(&self, pos: GridPoint<C>) -> Option<C> {
+        self.access(pos)
+            .iter()
+            .find(|cell| cell.last_access.get() != 0 && cell.pos.get() == pos)
+            .map(|cell| cell.chunk.borrow().clone())
     }
 
-    pub fn clear(&self, pos: GridPoint<C>, layer: &C::Dependencies) {
+    fn clear(&self, pos: GridPoint<C>, layer: &C::Dependencies) {
         for cell in self.access(pos) {
-            if cell.pos.get() == pos {
+            if cell.last_access.get() != 0 && cell.pos.get() == pos {
                 cell.last_access.set(0);
                 let prev = cell.chunk.replace(Default::default());
                 prev.on_drop(layer, pos);
@@ -293,50 +534,216 @@ impl<C: Chunk> RollingGrid<C> {
         C::clear(layer, pos)
     }
 
-    pub fn incoherent_override_cache(&self, layer: &C::Dependencies, pos: GridPoint<C>, val: C) {
+    fn set(&self, layer: &C::Dependencies, pos: GridPoint<C>, val: C) {
         let now = self.time.get();
         self.time.set(now.checked_add(1).unwrap());
-        let (Ok(v) | Err(v)) = self.find_free_or_entry(pos, now);
+        let (v, occupied) = match self.find_free_or_entry(pos, now) {
+            Ok(v) => {
+                let occupied = v.last_access.get() != 0;
+                if occupied {
+                    self.stats.eviction();
+                }
+                (v, occupied)
+            }
+            Err(v) => (v, true),
+        };
         let prev = v.chunk.replace(val);
-        let prev_pos = v.pos.replace(pos);
-        prev.on_drop(layer, prev_pos);
+        let prev_pos = v.replace_pos(pos);
+        v.last_access.set(now);
+        v.version.set(self.deps_version.get(|| layer.version()));
+        if occupied {
+            prev.on_drop(layer, prev_pos);
+        }
     }
 
-    pub const fn pos_to_grid_pos(pos: Point2d) -> GridPoint<C> {
+    fn iter_all_loaded(&self) -> impl Iterator<Item = (GridPoint<C>, C)> + '_ {
+        self.grid
+            .iter()
+            .flatten()
+            .filter(|cell| cell.last_access.get() != 0)
+            .map(|cell| (cell.pos.get(), cell.chunk.borrow().clone()))
+    }
+}
+
+/// Only the cached chunks are serialized, each with its position, from the least to the most
+/// recently used one, so a restored grid evicts chunks in the same order.
+///
+/// Nothing else is: the grids of dependency layers are not part of this grid, so serialize
+/// each of them separately, and a layer shared by several others just once. The dependency
+/// versions of the chunks are not kept either, because the [Layer::bump_version] calls they
+/// stem from aren't. Restored chunks count as computed before any bump.
+///
+/// [Layer::bump_version]: crate::Layer::bump_version
+#[cfg(feature = "serde")]
+impl<C: Chunk + serde::Serialize> serde::Serialize for RollingGrid<C> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut cells: Vec<_> = self
+            .grid
+            .iter()
+            .flatten()
+            .filter(|cell| cell.last_access.get() != 0)
+            .collect();
+        cells.sort_by_key(|cell| cell.last_access.get());
+        serializer.collect_seq(cells.into_iter().map(|cell| {
+            let pos = cell.pos.get();
+            (pos.x.0, pos.y.0, cell.chunk.borrow().clone())
+        }))
+    }
+}
+
+/// Restores the cached chunks, so [ChunkGrid::get] returns them without computing them again.
+/// If more chunks map to the same grid cell than [Chunk::GRID_OVERLAP] allows, the least recently
+/// used ones are dropped without invoking [Chunk::on_drop], as there are no dependencies to pass to it.
+#[cfg(feature = "serde")]
+impl<'de, C: Chunk + serde::Deserialize<'de>> serde::Deserialize<'de> for RollingGrid<C> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let grid = Self::default();
+        for (x, y, chunk) in Vec::<(i64, i64, C)>::deserialize(deserializer)? {
+            grid.restore(
+                GridPoint::new(GridIndex::from_raw(x), GridIndex::from_raw(y)),
+                chunk,
+            );
+        }
+        Ok(grid)
+    }
+}
+
+impl<C: Chunk> RollingGrid<C> {
+    /// Like [ChunkGrid::set], but without dependencies to invoke [Chunk::on_drop] with.
+    #[cfg(feature = "serde")]
+    fn restore(&self, pos: GridPoint<C>, val: C) {
+        let now = self.time.get();
+        self.time.set(now.checked_add(1).unwrap());
+        let cell = match self.find_free_or_entry(pos, now) {
+            Ok(cell) | Err(cell) => cell,
+        };
+        cell.chunk.replace(val);
+        cell.replace_pos(pos);
+        cell.last_access.set(now);
+        // The dependency versions are not serialized.
+        cell.version.set(0);
+    }
+
+    /// The world coordinates of the chunk at `pos`, if it is currently cached.
+    /// Same as [ChunkExt::bounds](crate::ChunkExt::bounds), but remembered from
+    /// when the chunk got cached instead of recomputed.
+    pub fn bounds_of(&self, pos: GridPoint<C>) -> Option<Bounds> {
+        self.access(pos)
+            .iter()
+            .find(|cell| cell.last_access.get() != 0 && cell.pos.get() == pos)
+            .map(|cell| cell.bounds.get())
+    }
+
+    /// The cached chunk closest to the world position `pos`, e.g. to render something while
+    /// the chunk at `pos` is still being generated. Distances are measured to the closest
+    /// point of each chunk, so the chunk containing `pos` is returned if it is cached.
+    /// Ties are broken by the smaller index.
+    pub fn nearest_resident(&self, pos: Point2d) -> Option<(GridPoint<C>, C)> {
+        let cell = self
+            .grid
+            .iter()
+            .flatten()
+            .filter(|cell| cell.last_access.get() != 0)
+            .min_by_key(|cell| (cell.bounds.get().manhattan_distance_to(pos), cell.pos.get()))?;
+        Some((cell.pos.get(), cell.chunk.borrow().clone()))
+    }
+
+    /// A copy of the current usage counters. Diff two snapshots to get
+    /// e.g. the rates for a frame or a second.
+    ///
+    /// Counting is a cheap atomic increment, but [GridStats::occupied_slots] is
+    /// computed by looking at all slots of the grid.
+    pub fn stats(&self) -> GridStats {
+        let occupied_slots = self
+            .grid
+            .iter()
+            .flatten()
+            .filter(|cell| cell.last_access.get() != 0)
+            .count();
+        self.stats.snapshot(occupied_slots)
+    }
+
+    /// Set all usage counters back to zero.
+    pub fn reset_stats(&self) {
+        self.stats.reset();
+    }
+
+    fn find_free_or_entry(
+        &self,
+        pos: Point2d<GridIndex<C>>,
+        now: u64,
+    ) -> Result<&ActiveCell<C>, &ActiveCell<C>> {
+        let mut free: Option<&ActiveCell<C>> = None;
+        for p in self.access(pos) {
+            if p.last_access.get() != 0 && p.pos.get() == pos {
+                p.last_access.set(now);
+                return Err(p);
+            }
+            // Prefer never used cells, then the least recently used one.
+            if free.is_none_or(|free| p.last_access < free.last_access) {
+                free = Some(p);
+            }
+        }
+        Ok(free.expect("GRID_OVERLAP must not be zero"))
+    }
+
+    pub(crate) const fn pos_to_grid_pos(pos: Point2d) -> GridPoint<C> {
         GridPoint {
             x: GridIndex::from_raw(pos.x >> C::SIZE.x),
             y: GridIndex::from_raw(pos.y >> C::SIZE.y),
         }
     }
 
-    const fn index_of_point(point: GridPoint<C>) -> usize {
-        const { assert!((C::GRID_SIZE.x as u32) < usize::BITS) }
-        const { assert!((C::GRID_SIZE.y as u32) < usize::BITS) }
+    /// [ChunkGrid::first_overflowing] for a grid of `2^size.x * 2^size.y` cells holding
+    /// `overlap` chunks each.
+    pub(crate) fn first_overflowing_in(
+        bounds: GridBounds<C>,
+        size: Point2d<u8>,
+        overlap: usize,
+    ) -> Option<GridPoint<C>> {
+        if bounds.is_empty() {
+            return None;
+        }
+        // The cell of `min` gets the most chunks: every `2^size`th one along each axis.
+        let per_axis = |min: GridIndex<C>, max: GridIndex<C>, size: u8| {
+            max.0.abs_diff(min.0).div_ceil(1 << size)
+        };
+        let columns = per_axis(bounds.min.x, bounds.max.x, size.x);
+        let rows = per_axis(bounds.min.y, bounds.max.y, size.y);
+        let overlap = u64::try_from(overlap).unwrap_or(u64::MAX);
+        if u128::from(columns) * u128::from(rows) <= u128::from(overlap) {
+            return None;
+        }
+        // The chunk after the first `overlap` ones in that cell, which are in rows of `columns` chunks.
+        let offset = |min: GridIndex<C>, steps: u64, size: u8| {
+            GridIndex::from_raw(min.0.checked_add_unsigned(steps << size).unwrap())
+        };
+        Some(Point2d::new(
+            offset(bounds.min.x, overlap % columns, size.x),
+            offset(bounds.min.y, overlap / columns, size.y),
+        ))
+    }
+
+    /// The position of `point` in a grid of `2^size.x * 2^size.y` cells.
+    /// The grid constructors check that `size.x + size.y` is smaller than the bits of [usize].
+    pub(crate) const fn index_of_point(point: GridPoint<C>, size: Point2d<u8>) -> usize {
         #[expect(
             clippy::cast_possible_truncation,
-            reason = "checked above that remainder op will alway fit in usize"
+            reason = "the size is checked on construction so the remainder will always fit in usize"
         )]
-        let x = point.x.0.rem_euclid(1 << C::GRID_SIZE.x) as usize;
+        let x = point.x.0.rem_euclid(1 << size.x) as usize;
         #[expect(
             clippy::cast_possible_truncation,
-            reason = "checked above that remainder op will alway fit in usize"
+            reason = "the size is checked on construction so the remainder will always fit in usize"
         )]
-        let y = point.y.0.rem_euclid(1 << C::GRID_SIZE.y) as usize;
-        x + (y << C::GRID_SIZE.x)
+        let y = point.y.0.rem_euclid(1 << size.y) as usize;
+        x + (y << size.x)
     }
 
     #[track_caller]
     fn access(&self, pos: GridPoint<C>) -> &[ActiveCell<C>] {
         self.grid
-            .get(Self::index_of_point(pos))
+            .get(Self::index_of_point(pos, self.size))
             .unwrap_or_else(|| panic!("grid position {pos:?} out of bounds"))
     }
-
-    pub fn iter_all_loaded(&self) -> impl Iterator<Item = (GridPoint<C>, C)> + '_ {
-        self.grid
-            .iter()
-            .flatten()
-            .filter(|cell| cell.last_access.get() != 0)
-            .map(|cell| (cell.pos.get(), cell.chunk.borrow().clone()))
-    }
 }
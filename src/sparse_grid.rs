@@ -0,0 +1,93 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use crate::{Chunk, ChunkGrid, Dependencies as _, Freshness, GridPoint, VersionCache};
+
+/// A cache of chunks keyed by their position.
+///
+/// In contrast to [RollingGrid](crate::RollingGrid) there is no limit on how far apart
+/// the cached chunks can be, so it is well suited for layers that only get accessed at a few
+/// far-apart positions (e.g. by a pathfinder). Chunks are never evicted automatically,
+/// only when explicitly cleared via [Layer::clear](crate::Layer::clear).
+///
+/// The chunks are kept sorted by position, so iterating over them is in the same
+/// order on every machine and in every process.
+pub struct SparseGrid<C: Chunk> {
+    /// The chunks and the [Dependencies::version](crate::Dependencies::version) they were computed with.
+    chunks: RefCell<BTreeMap<GridPoint<C>, (C, u64)>>,
+    deps_version: VersionCache,
+}
+
+impl<C: Chunk> Default for SparseGrid<C> {
+    fn default() -> Self {
+        Self {
+            chunks: Default::default(),
+            deps_version: Default::default(),
+        }
+    }
+}
+
+impl<C: Chunk> ChunkGrid<C> for SparseGrid<C> {
+    fn get(&self, pos: GridPoint<C>, layer: &C::Dependencies) -> (C, Freshness) {
+        let version = self.deps_version.get(|| layer.version());
+        if let Some((chunk, computed)) = self.chunks.borrow().get(&pos) {
+            if *computed == version {
+                return (chunk.clone(), Freshness::Cached);
+            }
+        }
+        // Do not hold the borrow while computing, the computation may
+        // end up clearing chunks of this layer.
+        let chunk = crate::compute(layer, pos);
+        self.insert(layer, pos, chunk.clone(), version);
+        (chunk, Freshness::Created)
+    }
+
+    fn set(&self, layer: &C::Dependencies, pos: GridPoint<C>, val: C) {
+        let version = self.deps_version.get(|| layer.version());
+        self.insert(layer, pos, val, version);
+    }
+
+    fn contains(&self, pos: GridPoint<C>) -> bool {
+        self.chunks.borrow().contains_key(&pos)
+    }
+
+    fn peek(&self, pos: GridPoint<C>) -> Option<C> {
+        self.chunks
+            .borrow()
+            .get(&pos)
+            .map(|(chunk, _)| chunk.clone())
+    }
+
+    fn clear(&self, pos: GridPoint<C>, layer: &C::Dependencies) {
+        let prev = self.chunks.borrow_mut().remove(&pos);
+        if let Some((prev, _)) = prev {
+            prev.on_drop(layer, pos);
+        }
+        C::clear(layer, pos)
+    }
+
+    fn iter_all_loaded(&self) -> impl Iterator<Item = (GridPoint<C>, C)> + '_ {
+        // Collect so we don't hold the borrow while the caller looks at the chunks.
+        let chunks: Vec<_> = self
+            .chunks
+            .borrow()
+            .iter()
+            .map(|(&pos, (chunk, _))| (pos, chunk.clone()))
+            .collect();
+        chunks.into_iter()
+    }
+
+    fn drop(&self, layer: &C::Dependencies) {
+        for (&pos, (chunk, _)) in self.chunks.borrow().iter() {
+            chunk.on_drop(layer, pos)
+        }
+    }
+}
+
+impl<C: Chunk> SparseGrid<C> {
+    fn insert(&self, layer: &C::Dependencies, pos: GridPoint<C>, val: C, version: u64) {
+        let prev = self.chunks.borrow_mut().insert(pos, (val, version));
+        if let Some((prev, _)) = prev {
+            prev.on_drop(layer, pos);
+        }
+    }
+}
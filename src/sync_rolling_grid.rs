@@ -0,0 +1,224 @@
+use std::sync::{
+    PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    Chunk, ChunkGrid, Dependencies as _, Freshness, GridBounds, GridIndex, GridPoint, GridStats,
+    RollingGrid, VersionCache, rolling_grid::GridCounters,
+};
+
+/// Like [RollingGrid], but can be shared between threads, e.g. to read the generated world
+/// from a render thread and a simulation thread at the same time.
+///
+/// Every grid cell has its own [RwLock], so threads only wait for each other when they write
+/// to the same cell. Chunks are computed without holding a lock, so two threads requesting the
+/// same missing chunk at the same time may both compute it, and the last one to finish replaces the other one's.
+/// Use it via [Chunk::Grid] with a [Chunk::LayerStore] of `Arc<T>` to share the [Layer](crate::Layer).
+pub struct SyncRollingGrid<C: Chunk> {
+    grid: Box<[Slots<C>]>,
+    time: AtomicU64,
+    stats: GridCounters,
+    deps_version: VersionCache,
+}
+
+/// The up to [Chunk::GRID_OVERLAP] chunks of a grid cell.
+type Slots<C> = RwLock<Box<[Slot<C>]>>;
+
+struct Slot<C: Chunk> {
+    pos: GridPoint<C>,
+    chunk: C,
+    /// Zero if the slot has never been used or was cleared.
+    last_access: AtomicU64,
+    /// The [Dependencies::version](crate::Dependencies::version) the chunk was computed with.
+    version: u64,
+}
+
+impl<C: Chunk> Default for SyncRollingGrid<C> {
+    fn default() -> Self {
+        const { assert!(C::GRID_OVERLAP > 0, "GRID_OVERLAP must not be zero") };
+        const { assert!((C::GRID_SIZE.x as u32) + (C::GRID_SIZE.y as u32) < usize::BITS) };
+        Self {
+            grid: std::iter::repeat_with(|| {
+                RwLock::new(
+                    std::iter::repeat_with(|| Slot {
+                        pos: GridPoint::splat(GridIndex::from_raw(i64::MIN)),
+                        chunk: C::default(),
+                        last_access: AtomicU64::new(0),
+                        version: 0,
+                    })
+                    .take(C::GRID_OVERLAP.into())
+                    .collect(),
+                )
+            })
+            .take((1 << C::GRID_SIZE.x) << C::GRID_SIZE.y)
+            .collect(),
+            time: AtomicU64::new(1),
+            stats: Default::default(),
+            deps_version: Default::default(),
+        }
+    }
+}
+
+impl<C: Chunk> SyncRollingGrid<C> {
+    /// Same as [RollingGrid::stats]. Can be called from any thread, also while other
+    /// threads are using the grid.
+    pub fn stats(&self) -> GridStats {
+        let occupied_slots = self
+            .grid
+            .iter()
+            .map(|cell| {
+                let slots = cell.read().unwrap_or_else(PoisonError::into_inner);
+                slots
+                    .iter()
+                    .filter(|slot| slot.last_access.load(Ordering::Relaxed) != 0)
+                    .count()
+            })
+            .sum();
+        self.stats.snapshot(occupied_slots)
+    }
+
+    /// Set all usage counters back to zero.
+    pub fn reset_stats(&self) {
+        self.stats.reset();
+    }
+
+    fn tick(&self) -> u64 {
+        self.time.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn cell(&self, pos: GridPoint<C>) -> &Slots<C> {
+        self.grid
+            .get(RollingGrid::<C>::index_of_point(pos, C::GRID_SIZE))
+            .unwrap_or_else(|| panic!("grid position {pos:?} out of bounds"))
+    }
+
+    // Chunks are only replaced as a whole, so a panic while holding a lock can't leave
+    // a slot half updated.
+    fn read(&self, pos: GridPoint<C>) -> RwLockReadGuard<'_, Box<[Slot<C>]>> {
+        self.cell(pos)
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self, pos: GridPoint<C>) -> RwLockWriteGuard<'_, Box<[Slot<C>]>> {
+        self.cell(pos)
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn insert(&self, layer: &C::Dependencies, pos: GridPoint<C>, val: C, version: u64) {
+        let now = self.tick();
+        let prev = {
+            let mut slots = self.write(pos);
+            let (slot, evicting) = match slots
+                .iter()
+                .position(|slot| slot.last_access.load(Ordering::Relaxed) != 0 && slot.pos == pos)
+            {
+                Some(i) => (&mut slots[i], false),
+                // Prefer never used slots, then the least recently used one.
+                None => (
+                    slots
+                        .iter_mut()
+                        .min_by_key(|slot| slot.last_access.load(Ordering::Relaxed))
+                        .expect("GRID_OVERLAP must not be zero"),
+                    true,
+                ),
+            };
+            let occupied = *slot.last_access.get_mut() != 0;
+            if occupied && evicting {
+                self.stats.eviction();
+            }
+            *slot.last_access.get_mut() = now;
+            slot.version = version;
+            let prev_pos = std::mem::replace(&mut slot.pos, pos);
+            let prev = std::mem::replace(&mut slot.chunk, val);
+            occupied.then_some((prev, prev_pos))
+        };
+        // Outside of the lock, as it may access this layer.
+        if let Some((prev, prev_pos)) = prev {
+            prev.on_drop(layer, prev_pos);
+        }
+    }
+
+    fn find(slots: &[Slot<C>], pos: GridPoint<C>) -> Option<&Slot<C>> {
+        slots
+            .iter()
+            .find(|slot| slot.last_access.load(Ordering::Relaxed) != 0 && slot.pos == pos)
+    }
+}
+
+impl<C: Chunk> ChunkGrid<C> for SyncRollingGrid<C> {
+    fn first_overflowing(&self, bounds: GridBounds<C>) -> Option<GridPoint<C>> {
+        RollingGrid::first_overflowing_in(bounds, C::GRID_SIZE, C::GRID_OVERLAP.into())
+    }
+
+    fn get(&self, pos: GridPoint<C>, layer: &C::Dependencies) -> (C, Freshness) {
+        let now = self.tick();
+        let version = self.deps_version.get(|| layer.version());
+        if let Some(slot) = Self::find(&self.read(pos), pos) {
+            if slot.version == version {
+                slot.last_access.store(now, Ordering::Relaxed);
+                self.stats.cache_hit();
+                return (slot.chunk.clone(), Freshness::Cached);
+            }
+        }
+        self.stats.compute();
+        // Do not hold the lock while computing, the computation may
+        // end up accessing chunks of this layer.
+        let chunk = crate::compute(layer, pos);
+        self.insert(layer, pos, chunk.clone(), version);
+        (chunk, Freshness::Created)
+    }
+
+    fn set(&self, layer: &C::Dependencies, pos: GridPoint<C>, val: C) {
+        let version = self.deps_version.get(|| layer.version());
+        self.insert(layer, pos, val, version);
+    }
+
+    fn contains(&self, pos: GridPoint<C>) -> bool {
+        Self::find(&self.read(pos), pos).is_some()
+    }
+
+    fn peek(&self, pos: GridPoint<C>) -> Option<C> {
+        Self::find(&self.read(pos), pos).map(|slot| slot.chunk.clone())
+    }
+
+    fn clear(&self, pos: GridPoint<C>, layer: &C::Dependencies) {
+        let prev = {
+            let mut slots = self.write(pos);
+            slots
+                .iter_mut()
+                .find(|slot| slot.last_access.load(Ordering::Relaxed) != 0 && slot.pos == pos)
+                .map(|slot| {
+                    *slot.last_access.get_mut() = 0;
+                    std::mem::take(&mut slot.chunk)
+                })
+        };
+        if let Some(prev) = prev {
+            prev.on_drop(layer, pos);
+        }
+        C::clear(layer, pos)
+    }
+
+    fn iter_all_loaded(&self) -> impl Iterator<Item = (GridPoint<C>, C)> + '_ {
+        // Collect so we don't hold the locks while the caller looks at the chunks.
+        let mut chunks = vec![];
+        for cell in &self.grid {
+            let slots = cell.read().unwrap_or_else(PoisonError::into_inner);
+            chunks.extend(
+                slots
+                    .iter()
+                    .filter(|slot| slot.last_access.load(Ordering::Relaxed) != 0)
+                    .map(|slot| (slot.pos, slot.chunk.clone())),
+            );
+        }
+        chunks.into_iter()
+    }
+
+    fn drop(&self, layer: &C::Dependencies) {
+        for (pos, chunk) in self.iter_all_loaded() {
+            chunk.on_drop(layer, pos)
+        }
+    }
+}
@@ -54,7 +54,7 @@ impl<T: std::hash::Hash> std::hash::Hash for Point2d<T> {
 }
 
 /// A line segment with a direction.
-#[derive(PartialEq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
 pub struct Line<T = i64> {
     /// The start of the line segment.
     pub start: Point2d<T>,
@@ -64,41 +64,95 @@ pub struct Line<T = i64> {
 
 impl Line {
     /// Returns a point where two line segments intersect (if any).
-    // impl from https://stackoverflow.com/a/14795484
+    #[deprecated = "use `Line::intersection`, which also handles collinear segments"]
     pub fn get_intersection(self, other: Self) -> Option<Point2d> {
-        let s10 = self.end - self.start;
-        let s32 = other.end - other.start;
-
-        let denom = s10.x * s32.y - s32.x * s10.y;
-        if denom == 0 {
-            return None; // Collinear
-        }
-        let denom_positive = denom > 0;
+        self.intersection(&other)
+    }
 
-        let s02 = self.start - other.start;
-        let s_numer = s10.x * s02.y - s10.y * s02.x;
-        if (s_numer < 0) == denom_positive {
-            return None; // No collision
-        }
-        let t_numer = s32.x * s02.y - s32.y * s02.x;
-        if (t_numer < 0) == denom_positive {
-            return None; // No collision
+    /// Returns the point where two line segments cross, rounded to the nearest integer position.
+    /// Touching at an end point counts as crossing. If the segments are collinear and overlap,
+    /// returns the shared point closest to `self.start`.
+    ///
+    /// Decides exactly whether the segments cross, so they may be anywhere in the world,
+    /// even spanning all of it.
+    pub fn intersection(&self, other: &Self) -> Option<Point2d> {
+        let r = wide(self.end) - wide(self.start);
+        let s = wide(other.end) - wide(other.start);
+        let qp = wide(other.start) - wide(self.start);
+        let denom = cross_sign(r, s);
+        if denom == Ordering::Equal {
+            // Parallel, so they can only share points if they are on the same line.
+            if cross_sign(qp, r) != Ordering::Equal {
+                return None;
+            }
+            if other.contains_point(self.start) {
+                return Some(self.start);
+            }
+            return [other.start, other.end]
+                .into_iter()
+                .filter(|&p| self.contains_point(p))
+                .min_by_key(|&p| {
+                    // All points are on one line, so this orders them like their distance.
+                    let d = wide(p) - wide(self.start);
+                    d.x.unsigned_abs() + d.y.unsigned_abs()
+                });
         }
-        if (s_numer > denom) == denom_positive || (t_numer > denom) == denom_positive {
-            return None; // No collision
+        // `self.start + r * t / denom == other.start + s * u / denom` with `t = cross(qp, s)`
+        // and `u = cross(qp, r)`. Both fractions are within `0..=1` if neither the numerators nor
+        // `denom - t == cross(self.end - other.start, s)` and `denom - u == cross(r, other.end - self.start)`
+        // have the opposite sign of `denom`.
+        let within = |sign: Ordering| sign != denom.reverse();
+        if !(within(cross_sign(qp, s))
+            && within(cross_sign(wide(self.end) - wide(other.start), s))
+            && within(cross_sign(qp, r))
+            && within(cross_sign(r, wide(other.end) - wide(self.start))))
+        {
+            return None;
         }
-        // Collision detected
-        let t = t_numer / denom;
+        // Relative to `start`, so far away positions don't lose precision.
+        let t = match (cross(qp, s), cross(r, s)) {
+            (Some(t), Some(denom)) => t as f64 / denom as f64,
+            // The segments span more than half of the world, where `f64` is too coarse
+            // for exact positions anyway.
+            _ => (cross_f64(qp, s) / cross_f64(r, s)).clamp(0., 1.),
+        };
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "the result is clamped to the line segment, which fits in i64"
+        )]
+        let along = |start: i64, end: i64, r: i128| {
+            let pos = i128::from(start) + (r as f64 * t).round() as i128;
+            pos.clamp(start.min(end).into(), start.max(end).into()) as i64
+        };
+        Some(Point2d::new(
+            along(self.start.x, self.end.x, r.x),
+            along(self.start.y, self.end.y, r.y),
+        ))
+    }
+
+    /// Whether the two line segments cross or touch, see [Line::intersection].
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
 
-        Some(self.start + s10 * t)
+    /// Whether `point` is exactly on the line segment.
+    fn contains_point(&self, point: Point2d) -> bool {
+        cross_sign(
+            wide(self.end) - wide(self.start),
+            wide(point) - wide(self.start),
+        ) == Ordering::Equal
+            && self.start.x.min(self.end.x) <= point.x
+            && point.x <= self.start.x.max(self.end.x)
+            && self.start.y.min(self.end.y) <= point.y
+            && point.y <= self.start.y.max(self.end.y)
     }
 
-    /// Create bounds where this line is the diagonal of.
+    /// The smallest bounds that [contain](Bounds::contains) both end points of this line,
+    /// so the line is their diagonal (ending one position before `max`).
     pub fn bounds(&self) -> Bounds {
-        Bounds {
-            min: self.start,
-            max: self.end,
-        }
+        let mut bounds = Bounds::point(self.start);
+        bounds.expand_to_include(self.end);
+        bounds
     }
 
     /// Shorten the line to make its manhattan length the given one.
@@ -125,6 +179,142 @@ impl Line {
     pub fn len_squared(&self) -> i64 {
         (self.end - self.start).len_squared()
     }
+
+    /// Order the end points so that `start` is the smaller one (by x, then y).
+    /// Two lines covering the same segment in opposite directions are equal after this.
+    pub fn canonicalize(self) -> Self {
+        if self.end < self.start {
+            self.flip()
+        } else {
+            self
+        }
+    }
+
+    /// The point at `t` along the line, where `0` is `start` and `1` is `end`.
+    /// Rounded to the nearest integer position.
+    pub fn point_at(&self, t: f64) -> Point2d {
+        self.start.lerp(self.end, t)
+    }
+
+    /// Whether both lines cover the same segment, independently of their direction.
+    pub fn same_segment(&self, other: &Self) -> bool {
+        self.canonicalize() == other.canonicalize()
+    }
+
+    /// Cut off the parts of the line segment that are outside the area of the given bounds.
+    /// Lines are continuous, so parts on the border at `max` are kept, too.
+    /// Returns `None` if no part of the line is within the area.
+    /// The new end points are rounded to the nearest integer position.
+    pub fn clip(self, bounds: Bounds) -> Option<Self> {
+        // https://en.wikipedia.org/wiki/Liang%E2%80%93Barsky_algorithm
+        // Differences of far apart coordinates don't fit into an `i64`.
+        let (start, min, max) = (wide(self.start), wide(bounds.min), wide(bounds.max));
+        let dir = wide(self.end) - start;
+        // The parameters of the new end points, and the border they were moved onto, if any.
+        // Their coordinate on that border is exact, even where floats are not.
+        let mut t0 = (0.0_f64, Point2d::splat(None));
+        let mut t1 = (1.0_f64, Point2d::splat(None));
+        for (p, q, border) in [
+            (
+                -dir.x,
+                start.x - min.x,
+                Point2d::new(Some(bounds.min.x), None),
+            ),
+            (
+                dir.x,
+                max.x - start.x,
+                Point2d::new(Some(bounds.max.x), None),
+            ),
+            (
+                -dir.y,
+                start.y - min.y,
+                Point2d::new(None, Some(bounds.min.y)),
+            ),
+            (
+                dir.y,
+                max.y - start.y,
+                Point2d::new(None, Some(bounds.max.y)),
+            ),
+        ] {
+            if p == 0 {
+                // Parallel to this border, so either fully inside or outside of it.
+                if q < 0 {
+                    return None;
+                }
+                continue;
+            }
+            let r = q as f64 / p as f64;
+            if p < 0 {
+                if r > t0.0 {
+                    t0 = (r, border);
+                }
+            } else if r < t1.0 {
+                t1 = (r, border);
+            }
+            if t0.0 > t1.0 {
+                return None;
+            }
+        }
+        let at = |(t, border): (f64, Point2d<Option<i64>>)| {
+            if t <= 0.0 {
+                return self.start;
+            }
+            if t >= 1.0 {
+                // `dir` may be rounded as a float.
+                return self.end;
+            }
+            let axis = |start: i64, end: i64, dir: i128, border: Option<i64>| {
+                if let Some(border) = border {
+                    return border;
+                }
+                #[expect(
+                    clippy::cast_possible_truncation,
+                    reason = "`t` is within 0..1, so the offset is at most `dir`"
+                )]
+                let offset = (dir as f64 * t).round() as i128;
+                // Rounding can't leave the line segment, whose coordinates fit in i64.
+                let pos = (i128::from(start) + offset)
+                    .clamp(start.min(end).into(), start.max(end).into());
+                i64::try_from(pos).unwrap()
+            };
+            Point2d::new(
+                axis(self.start.x, self.end.x, dir.x, border.x),
+                axis(self.start.y, self.end.y, dir.y, border.y),
+            )
+        };
+        Some(Self {
+            start: at(t0),
+            end: at(t1),
+        })
+    }
+}
+
+fn wide(p: Point2d) -> Point2d<i128> {
+    p.map(i128::from)
+}
+
+/// The cross product, or `None` if it overflows, which differences of [i64] coordinates can.
+fn cross(a: Point2d<i128>, b: Point2d<i128>) -> Option<i128> {
+    a.x.checked_mul(b.y)?.checked_sub(a.y.checked_mul(b.x)?)
+}
+
+fn cross_f64(a: Point2d<i128>, b: Point2d<i128>) -> f64 {
+    a.x as f64 * b.y as f64 - a.y as f64 * b.x as f64
+}
+
+/// The sign of the cross product, exact for all differences of [i64] coordinates.
+fn cross_sign(a: Point2d<i128>, b: Point2d<i128>) -> Ordering {
+    // The magnitudes of the products fit into a `u128`, so compare them with their signs.
+    let product = |p: i128, q: i128| {
+        let magnitude = p.unsigned_abs() * q.unsigned_abs();
+        (magnitude != 0 && (p < 0) != (q < 0), magnitude)
+    };
+    match (product(a.x, b.y), product(a.y, b.x)) {
+        ((false, l), (false, r)) => l.cmp(&r),
+        ((true, l), (true, r)) => r.cmp(&l),
+        ((false, _), (true, _)) => Ordering::Greater,
+        ((true, _), (false, _)) => Ordering::Less,
+    }
 }
 
 impl<T: Num> Line<T> {
@@ -260,6 +450,18 @@ impl Abs for i64 {
     }
 }
 
+impl Abs for f32 {
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+}
+
+impl Abs for f64 {
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+}
+
 impl<T: Copy + Sub<Output = T> + Mul<Output = T> + Add<Output = T> + Abs> Point2d<T> {
     /// The square of the distance between two points
     pub fn dist_squared(self, center: Point2d<T>) -> T {
@@ -308,6 +510,32 @@ impl Point2d<i64> {
         self
     }
 
+    /// The chebyshev (chess king) distance between two points: the larger of the
+    /// distances along each axis. Unsigned, so it doesn't overflow for points far apart.
+    pub fn chebyshev_dist(self, other: Point2d) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Whether the (euclidean) distance between the two points is at most `radius`.
+    /// Unlike comparing [Point2d::dist_squared] with `radius * radius`, this computes
+    /// in `i128`, so it doesn't overflow for points far apart or far from the origin.
+    pub fn within_radius(&self, other: Point2d, radius: i64) -> bool {
+        if radius < 0 {
+            return false;
+        }
+        let axis = |a: i64, b: i64| (i128::from(a) - i128::from(b)).unsigned_abs().pow(2);
+        let radius = i128::from(radius).unsigned_abs().pow(2);
+        axis(self.x, other.x)
+            .checked_add(axis(self.y, other.y))
+            .is_some_and(|dist| dist <= radius)
+    }
+
+    /// Convert to floating point coordinates, e.g. for sub-pixel positions.
+    /// Coordinates further than `2^24` from the origin are rounded.
+    pub fn as_f32(self) -> Point2d<f32> {
+        self.map(|i| i as f32)
+    }
+
     /// Get the bytes of this point in native byte order.
     pub fn to_ne_bytes(&self) -> [u8; 16] {
         let mut array = [0; 16];
@@ -321,6 +549,232 @@ impl Point2d<i64> {
     }
 }
 
+impl Point2d<f32> {
+    /// Round down to the integer position that contains this point.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "truncation after flooring is intended, values saturate at the limits of i64"
+    )]
+    pub fn as_i64(self) -> Point2d {
+        self.map(|f| f.floor() as i64)
+    }
+}
+
+/// How coordinate arithmetic behaves when it reaches the limits of [i64].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoordMode {
+    /// The world is infinite, so reaching the limits is a bug. Like normal
+    /// integer arithmetic this panics with debug assertions enabled and wraps otherwise.
+    #[default]
+    Infinite,
+    /// The world wraps around at the limits, so going past the right edge ends
+    /// up at the left edge.
+    Toroidal,
+    /// The world ends at the limits, going further just stays at the edge.
+    Bounded,
+}
+
+impl CoordMode {
+    /// Add two coordinates.
+    #[track_caller]
+    pub const fn add(self, a: i64, b: i64) -> i64 {
+        match self {
+            CoordMode::Infinite => a + b,
+            CoordMode::Toroidal => a.wrapping_add(b),
+            CoordMode::Bounded => a.saturating_add(b),
+        }
+    }
+
+    /// Subtract two coordinates.
+    #[track_caller]
+    pub const fn sub(self, a: i64, b: i64) -> i64 {
+        match self {
+            CoordMode::Infinite => a - b,
+            CoordMode::Toroidal => a.wrapping_sub(b),
+            CoordMode::Bounded => a.saturating_sub(b),
+        }
+    }
+
+    /// Multiply two coordinates.
+    #[track_caller]
+    pub const fn mul(self, a: i64, b: i64) -> i64 {
+        match self {
+            CoordMode::Infinite => a * b,
+            CoordMode::Toroidal => a.wrapping_mul(b),
+            CoordMode::Bounded => a.saturating_mul(b),
+        }
+    }
+}
+
+impl Point2d<i64> {
+    /// Linearly interpolate between `self` (at `t == 0`) and `other` (at `t == 1`),
+    /// rounded to the nearest integer position.
+    /// The difference is computed in `i128`, so the points may be anywhere in the world.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "the result is clamped to i64, which it only leaves for t outside of 0..=1"
+    )]
+    pub fn lerp(self, other: Point2d, t: f64) -> Point2d {
+        let lerp = |a: i64, b: i64| {
+            let offset = (i128::from(b) - i128::from(a)) as f64 * t;
+            let pos = i128::from(a) + offset.floor() as i128;
+            // Round the position, not the offset: halfway cases go away from zero.
+            let frac = offset - offset.floor();
+            let pos = pos + i128::from(frac > 0.5 || (frac == 0.5 && pos >= 0));
+            pos.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+        };
+        Point2d::new(lerp(self.x, other.x), lerp(self.y, other.y))
+    }
+
+    /// Rotate around `pivot` by `radians`, turning the x axis towards the y axis.
+    /// See [Affine2] for how the result is rounded.
+    pub fn rotate_around(self, pivot: Point2d, radians: f32) -> Point2d {
+        Affine2::rotate_around(pivot, radians).apply(self)
+    }
+
+    /// Add two points element wise, handling the limits of [i64] as specified by `mode`.
+    #[track_caller]
+    pub const fn add_with(self, rhs: Point2d, mode: CoordMode) -> Point2d {
+        Point2d::new(mode.add(self.x, rhs.x), mode.add(self.y, rhs.y))
+    }
+
+    /// Subtract two points element wise, handling the limits of [i64] as specified by `mode`.
+    #[track_caller]
+    pub const fn sub_with(self, rhs: Point2d, mode: CoordMode) -> Point2d {
+        Point2d::new(mode.sub(self.x, rhs.x), mode.sub(self.y, rhs.y))
+    }
+
+    /// Multiply two points element wise, handling the limits of [i64] as specified by `mode`.
+    #[track_caller]
+    pub const fn mul_with(self, rhs: Point2d, mode: CoordMode) -> Point2d {
+        Point2d::new(mode.mul(self.x, rhs.x), mode.mul(self.y, rhs.y))
+    }
+}
+
+/// A combination of translations, rotations and scaling, e.g. for placing oriented features.
+///
+/// Computed with [f64] and rounded to the nearest integer position when applied, with ties
+/// going to the even position, so e.g. scaling by `0.5` doesn't shift everything in one direction.
+/// Positions further than `2^53` from the origin can't be represented exactly and may be off.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine2 {
+    /// Where the x and y axes end up.
+    axes: [Point2d<f64>; 2],
+    offset: Point2d<f64>,
+}
+
+impl Affine2 {
+    /// Keeps every point where it is.
+    pub const IDENTITY: Self = Self {
+        axes: [Point2d::new(1., 0.), Point2d::new(0., 1.)],
+        offset: Point2d::new(0., 0.),
+    };
+
+    /// Move all points by `offset`.
+    pub fn translate(offset: Point2d) -> Self {
+        Self {
+            offset: offset.map(|i| i as f64),
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotate around the origin by `radians`, turning the x axis towards the y axis.
+    pub fn rotate(radians: f32) -> Self {
+        let (sin, cos) = f64::from(radians).sin_cos();
+        Self {
+            axes: [Point2d::new(cos, sin), Point2d::new(-sin, cos)],
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotate around `pivot` by `radians`.
+    pub fn rotate_around(pivot: Point2d, radians: f32) -> Self {
+        Self::translate(Point2d::splat(0) - pivot)
+            .then(Self::rotate(radians))
+            .then(Self::translate(pivot))
+    }
+
+    /// Scale the distance of all points to the origin by `factor`.
+    pub fn scale(factor: f32) -> Self {
+        let factor = f64::from(factor);
+        Self {
+            axes: [Point2d::new(factor, 0.), Point2d::new(0., factor)],
+            ..Self::IDENTITY
+        }
+    }
+
+    /// First apply `self`, then `next`.
+    pub fn then(self, next: Self) -> Self {
+        Self {
+            axes: self.axes.map(|axis| next.transform_vector(axis)),
+            offset: next.transform_vector(self.offset) + next.offset,
+        }
+    }
+
+    fn transform_vector(&self, v: Point2d<f64>) -> Point2d<f64> {
+        self.axes[0] * v.x + self.axes[1] * v.y
+    }
+
+    /// Transform a point, rounding to the nearest integer position.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "positions outside of i64 saturate, they were not representable anyway"
+    )]
+    pub fn apply(&self, point: Point2d) -> Point2d {
+        (self.transform_vector(point.map(|i| i as f64)) + self.offset)
+            .map(|f| f.round_ties_even() as i64)
+    }
+
+    /// Transform both end points of a line.
+    pub fn apply_line(&self, line: Line) -> Line {
+        self.apply(line.start).to(self.apply(line.end))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn affine() {
+    use std::f32::consts::FRAC_PI_2;
+    let pivot = Point2d::new(10, 10);
+    let line = Point2d::new(12, 10).to(Point2d::new(15, 13));
+    assert_eq!(
+        Affine2::rotate_around(pivot, FRAC_PI_2).apply_line(line),
+        Point2d::new(10, 12).to(Point2d::new(7, 15))
+    );
+    assert_eq!(
+        Point2d::new(15, 13).rotate_around(pivot, -FRAC_PI_2),
+        Point2d::new(13, 5)
+    );
+    assert_eq!(
+        Point2d::new(15, 13).rotate_around(pivot, 4. * FRAC_PI_2),
+        Point2d::new(15, 13)
+    );
+    // Ties round to even positions.
+    let half = Affine2::scale(0.5);
+    assert_eq!(half.apply(Point2d::new(3, 5)), Point2d::new(2, 2));
+    assert_eq!(half.apply(Point2d::new(-3, -5)), Point2d::new(-2, -2));
+    // Composition order.
+    let shift = Affine2::translate(Point2d::new(4, 0));
+    assert_eq!(
+        shift.then(half).apply(Point2d::new(2, 2)),
+        Point2d::new(3, 1)
+    );
+    assert_eq!(
+        half.then(shift).apply(Point2d::new(2, 2)),
+        Point2d::new(5, 1)
+    );
+    assert_eq!(
+        Affine2::IDENTITY.apply(Point2d::new(-7, 9)),
+        Point2d::new(-7, 9)
+    );
+    // Far from the origin, translations are still exact.
+    let far = Point2d::splat(1 << 50);
+    assert_eq!(
+        Affine2::translate(Point2d::new(1, -1)).apply(far),
+        far + Point2d::new(1, -1)
+    );
+}
+
 impl<T: DivAssign + Copy> Div<T> for Point2d<T> {
     type Output = Self;
     fn div(mut self, rhs: T) -> Self::Output {
@@ -351,23 +805,27 @@ impl<T: MulAssign + Copy> MulAssign<T> for Point2d<T> {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-/// A rectangle that includes the minimum and maximum values
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+/// A half-open rectangle `min..max`: it includes the minimum values, but not the maximum values,
+/// just like the [bounds of a chunk](crate::ChunkExt::bounds), so neighboring bounds don't overlap.
+/// Bounds with `min == max` on an axis contain no points at all.
 pub struct Bounds<T = i64> {
-    /// The corner closest to the origin.
+    /// The corner closest to the origin, the first point within the bounds.
     pub min: Point2d<T>,
-    /// The corner furthest away from the origin.
+    /// The corner furthest away from the origin, one past the last point within the bounds.
     pub max: Point2d<T>,
 }
 
 impl<T: std::fmt::Debug> std::fmt::Debug for Bounds<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}..={:?}", self.min, self.max)
+        write!(f, "{:?}..{:?}", self.min, self.max)
     }
 }
 
 impl<T: Copy + PartialEq + PartialOrd + SampleUniform> Bounds<T> {
-    /// Generate a point within the bounds.
+    /// Generate a point within the half-open bounds `min..max`.
+    /// Points at `max.x` or `max.y` are never generated,
+    /// matching how [Chunk](crate::Chunk)s own their bounds.
     pub fn sample<R: RngCore + ?Sized>(self, rng: &mut R) -> Point2d<T> {
         Point2d {
             x: (self.min.x..self.max.x).sample_single(rng).unwrap(),
@@ -394,27 +852,62 @@ impl<T: Copy + PartialEq + PartialOrd + SampleUniform> Bounds<T> {
     }
 }
 
-impl<T: Copy> Bounds<T> {
-    /// Bounds at a single point with zero width and height.
+impl<T: PartialOrd> Bounds<T> {
+    /// Whether `other` lies entirely within these bounds.
+    /// Equal bounds contain each other.
+    pub fn contains_bounds(&self, other: &Self) -> bool {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && other.max.x <= self.max.x
+            && other.max.y <= self.max.y
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn contains_bounds() {
+    let outer = Bounds {
+        min: Point2d::new(-5, -5),
+        max: Point2d::new(5, 5),
+    };
+    let inner = Bounds {
+        min: Point2d::new(-2, 0),
+        max: Point2d::new(3, 5),
+    };
+    let overlapping = Bounds {
+        min: Point2d::new(2, 2),
+        max: Point2d::new(7, 4),
+    };
+    assert!(outer.contains_bounds(&inner));
+    assert!(!inner.contains_bounds(&outer));
+    assert!(outer.contains_bounds(&outer));
+    assert!(!outer.contains_bounds(&overlapping));
+    assert!(!overlapping.contains_bounds(&outer));
+    assert!(!inner.contains_bounds(&overlapping));
+}
+
+impl<T: Num> Bounds<T> {
+    /// Bounds containing just `point`, so `max` is one past `point` on both axes.
     pub fn point(point: Point2d<T>) -> Self {
         Self {
             min: point,
-            max: point,
+            max: point + Point2d::splat(T::ONE),
         }
     }
 }
 
 impl<T: PartialOrd + Num + Copy + AddAssign> Bounds<T> {
-    /// Iterate over all integer points within these bounds.
+    /// Iterate over all integer points within these bounds, row by row.
+    /// These are exactly the points that [Bounds::contains] is `true` for.
     pub fn iter(self) -> impl Iterator<Item = Point2d<T>> {
         let mut current = self.min;
         std::iter::from_fn(move || {
-            if current.y > self.max.y {
+            if current.y >= self.max.y || self.min.x >= self.max.x {
                 None
             } else {
                 let item = current;
                 current.x += T::ONE;
-                if current.x > self.max.x {
+                if current.x >= self.max.x {
                     current.x = self.min.x;
                     current.y += T::ONE;
                 }
@@ -424,6 +917,500 @@ impl<T: PartialOrd + Num + Copy + AddAssign> Bounds<T> {
     }
 }
 
+impl<T: Num> Bounds<T> {
+    /// Whether these bounds contain no points at all, as `max` does not lie after `min` on an axis.
+    /// Iterating over empty bounds yields nothing.
+    pub fn is_empty(&self) -> bool {
+        self.min.x >= self.max.x || self.min.y >= self.max.y
+    }
+
+    /// Iterate over the points on the outermost rows and columns of these bounds
+    /// (so at `min` and at `max - 1`), each of them once.
+    pub fn iter_border(self) -> impl Iterator<Item = Point2d<T>> {
+        self.border_parts()
+            .into_iter()
+            .flatten()
+            .flat_map(Bounds::iter)
+    }
+
+    /// Iterate over all points within these bounds in rings of growing distance around `center`,
+    /// so the closest points come first. Visits the same points as [Bounds::iter] without allocating.
+    /// `center` is moved into the bounds if it is outside of them.
+    pub fn iter_spiral(self, center: Point2d<T>) -> impl Iterator<Item = Point2d<T>> {
+        let mut done = self.is_empty();
+        let center = if done {
+            center
+        } else {
+            Point2d::new(
+                center.x.clamp(self.min.x, self.max.x - T::ONE),
+                center.y.clamp(self.min.y, self.max.y - T::ONE),
+            )
+        };
+        let mut radius = T::ZERO;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let ring = Self {
+                min: center - Point2d::splat(radius),
+                max: center + Point2d::splat(radius + T::ONE),
+            };
+            done = ring.contains_bounds(&self);
+            radius += T::ONE;
+            Some(ring.border_parts())
+        })
+        .flatten()
+        .flatten()
+        .flat_map(move |part| part.intersect(self).iter())
+    }
+
+    /// The area covered by both bounds, or `None` if they don't overlap.
+    /// The bounds of neighboring chunks only touch, so they don't overlap.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let intersection = self.intersect(*other);
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    /// The smallest bounds covering both bounds.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Point2d::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point2d::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    /// Whether `point` is within these bounds, consistent with [Bounds::iter],
+    /// [Bounds::sample] and [ChunkExt::pos_to_grid](crate::ChunkExt::pos_to_grid).
+    pub fn contains(&self, point: Point2d<T>) -> bool {
+        (self.min.x..self.max.x).contains(&point.x) && (self.min.y..self.max.y).contains(&point.y)
+    }
+
+    /// Whether the bounds share any area, see [Bounds::intersection].
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Move `min` and `max` into `limit`, e.g. to clip a query to the generated part of the world.
+    /// In contrast to [Bounds::intersection] the result is never `None`, but may be empty
+    /// (at the border of `limit` closest to `self`) if the bounds don't overlap.
+    pub fn clamp_to(&self, limit: Self) -> Self {
+        let clamp = |p: Point2d<T>| {
+            Point2d::new(
+                p.x.clamp(limit.min.x, limit.max.x),
+                p.y.clamp(limit.min.y, limit.max.y),
+            )
+        };
+        Self {
+            min: clamp(self.min),
+            max: clamp(self.max),
+        }
+    }
+
+    /// Split these bounds into pieces of size `cell`, starting at `min`, row by row.
+    /// The pieces at the `max` borders are smaller if the size is not a multiple of `cell`.
+    /// The pieces don't overlap and together cover exactly these bounds.
+    #[track_caller]
+    pub fn subdivide(&self, cell: Point2d<T>) -> impl Iterator<Item = Self> {
+        assert!(
+            cell.x > T::ZERO && cell.y > T::ZERO,
+            "cell size must be positive"
+        );
+        let Self { min, max } = *self;
+        let steps = move |start: T, end: T, step: T| {
+            let mut next = start;
+            std::iter::from_fn(move || {
+                let start = next;
+                (start < end).then(|| {
+                    next = if end - start > step {
+                        start + step
+                    } else {
+                        end
+                    };
+                    (start, next)
+                })
+            })
+        };
+        steps(min.y, max.y, cell.y).flat_map(move |(min_y, max_y)| {
+            steps(min.x, max.x, cell.x).map(move |(min_x, max_x)| Self {
+                min: Point2d::new(min_x, min_y),
+                max: Point2d::new(max_x, max_y),
+            })
+        })
+    }
+
+    /// The overlap of both bounds, which is empty if they don't overlap.
+    fn intersect(self, other: Self) -> Self {
+        Self {
+            min: Point2d::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y)),
+            max: Point2d::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y)),
+        }
+    }
+
+    /// The rows and columns that make up [Bounds::iter_border], without overlaps.
+    fn border_parts(self) -> [Option<Self>; 4] {
+        let Self { min, max } = self;
+        let last = max - Point2d::splat(T::ONE);
+        let row = move |y| Bounds {
+            min: Point2d::new(min.x, y),
+            max: Point2d::new(max.x, y + T::ONE),
+        };
+        // Without the corners, which are part of the rows.
+        let column = move |x| Bounds {
+            min: Point2d::new(x, min.y + T::ONE),
+            max: Point2d::new(x + T::ONE, last.y),
+        };
+        if self.is_empty() {
+            [None; 4]
+        } else {
+            [
+                Some(row(min.y)),
+                (last.y > min.y).then(|| column(min.x)),
+                (last.y > min.y && last.x > min.x).then(|| column(last.x)),
+                (last.y > min.y).then(|| row(last.y)),
+            ]
+        }
+    }
+
+    /// The smallest bounds containing all `points`, or `None` if there are no points.
+    /// So `max` lies one past the largest coordinates and [Bounds::contains] is `true` for all `points`.
+    pub fn from_points(points: impl IntoIterator<Item = Point2d<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounds = Self::point(points.next()?);
+        for point in points {
+            bounds.expand_to_include(point);
+        }
+        Some(bounds)
+    }
+
+    /// Grow these bounds just enough to [contain](Bounds::contains) `point`.
+    pub fn expand_to_include(&mut self, point: Point2d<T>) {
+        let end = point + Point2d::splat(T::ONE);
+        self.min = Point2d::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point2d::new(self.max.x.max(end.x), self.max.y.max(end.y));
+    }
+
+    /// The manhattan distance from `point` to the closest point within these bounds.
+    /// Zero if the bounds [contain](Bounds::contains) `point`.
+    ///
+    /// In contrast to the distance to [Bounds::center], this does not depend on the size of the bounds,
+    /// so it is useful for e.g. choosing the level of detail of a chunk based on the player's distance.
+    pub fn manhattan_distance_to(&self, point: Point2d<T>) -> T {
+        let axis = |min: T, max: T, p: T| {
+            if p < min {
+                min - p
+            } else if p >= max {
+                p - (max - T::ONE)
+            } else {
+                T::ZERO
+            }
+        };
+        axis(self.min.x, self.max.x, point.x) + axis(self.min.y, self.max.y, point.y)
+    }
+
+    /// Iterate over the points of these bounds that are not within `other`.
+    fn iter_without(self, other: Self) -> impl Iterator<Item = Point2d<T>> {
+        let overlap = self.intersect(other);
+        let parts = if self.is_empty() {
+            [None; 4]
+        } else if overlap.is_empty() {
+            [Some(self), None, None, None]
+        } else {
+            [
+                // Full rows above and below the overlap.
+                (overlap.min.y > self.min.y).then(|| Bounds {
+                    min: self.min,
+                    max: Point2d::new(self.max.x, overlap.min.y),
+                }),
+                (overlap.max.y < self.max.y).then(|| Bounds {
+                    min: Point2d::new(self.min.x, overlap.max.y),
+                    max: self.max,
+                }),
+                // The rest of the rows left and right of the overlap.
+                (overlap.min.x > self.min.x).then(|| Bounds {
+                    min: Point2d::new(self.min.x, overlap.min.y),
+                    max: Point2d::new(overlap.min.x, overlap.max.y),
+                }),
+                (overlap.max.x < self.max.x).then(|| Bounds {
+                    min: Point2d::new(overlap.max.x, overlap.min.y),
+                    max: Point2d::new(self.max.x, overlap.max.y),
+                }),
+            ]
+        };
+        parts.into_iter().flatten().flat_map(Bounds::iter)
+    }
+
+    /// When moving from `old` to `new` bounds (e.g. a vision range), find the points that need
+    /// to be loaded (only in `new`) and the points that can be released (only in `old`).
+    /// The work is proportional to the number of changed points, not to the size of the bounds.
+    pub fn difference(
+        old: Self,
+        new: Self,
+    ) -> (
+        impl Iterator<Item = Point2d<T>>,
+        impl Iterator<Item = Point2d<T>>,
+    ) {
+        (new.iter_without(old), old.iter_without(new))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn iter_border() {
+    let bounds = Bounds {
+        min: Point2d::new(-2, 3),
+        max: Point2d::new(3, 7),
+    };
+    let border: Vec<_> = bounds.iter_border().collect();
+    let expected: Vec<_> = bounds
+        .iter()
+        .filter(|p| p.x == -2 || p.x == 2 || p.y == 3 || p.y == 6)
+        .collect();
+    assert_eq!(border.len(), expected.len());
+    for p in &expected {
+        assert!(border.contains(p), "{p:?}");
+    }
+    let column = Bounds {
+        min: Point2d::new(1, 1),
+        max: Point2d::new(2, 4),
+    };
+    assert_eq!(column.iter_border().count(), 3);
+    assert_eq!(Bounds::point(Point2d::new(1, 1)).iter_border().count(), 1);
+    let empty = Bounds {
+        min: Point2d::new(1, 1),
+        max: Point2d::new(0, 3),
+    };
+    assert_eq!(empty.iter_border().count(), 0);
+}
+
+#[cfg(test)]
+#[test]
+fn scalar_types() {
+    let a = Point2d::new(-3_i64, 4);
+    let b = Point2d::new(2_i64, -8);
+    assert_eq!(a.manhattan_dist(b), 17);
+    assert_eq!(a.dist_squared(b), 25 + 144);
+    assert_eq!(Point2d::splat(5_i64), Point2d::new(5, 5));
+    assert_eq!(a.as_f32().manhattan_dist(b.as_f32()), 17.0);
+    assert_eq!(a.as_f32().dist_squared(b.as_f32()), 169.0);
+    assert_eq!(Point2d::splat(0.5_f32), Point2d::new(0.5, 0.5));
+    assert_eq!(Point2d::new(1.5_f32, -0.25).as_i64(), Point2d::new(1, -1));
+    assert_eq!(a.as_f32().as_i64(), a);
+    assert_eq!(Point2d::splat(3_u8).map(u32::from), Point2d::new(3, 3));
+}
+
+#[cfg(test)]
+#[test]
+fn set_algebra() {
+    let bounds = |min: (i64, i64), max: (i64, i64)| Bounds {
+        min: Point2d::new(min.0, min.1),
+        max: Point2d::new(max.0, max.1),
+    };
+    let chunk = bounds((0, 0), (16, 16));
+    let right = bounds((16, 0), (32, 16));
+    let inner = bounds((4, 5), (8, 9));
+    let far = bounds((100, 100), (110, 120));
+    let crossing = bounds((10, -5), (20, 5));
+
+    // Touching at an edge.
+    assert_eq!(chunk.intersection(&right), None);
+    assert!(!chunk.overlaps(&right));
+    assert_eq!(chunk.union(&right), bounds((0, 0), (32, 16)));
+    assert!(!chunk.contains(right.min));
+    assert!(right.contains(right.min));
+    // Nested.
+    assert_eq!(chunk.intersection(&inner), Some(inner));
+    assert_eq!(inner.intersection(&chunk), Some(inner));
+    assert!(chunk.overlaps(&inner));
+    assert_eq!(chunk.union(&inner), chunk);
+    // Disjoint.
+    assert_eq!(chunk.intersection(&far), None);
+    assert!(!far.overlaps(&chunk));
+    assert_eq!(chunk.union(&far), bounds((0, 0), (110, 120)));
+    // Partially overlapping.
+    assert_eq!(
+        chunk.intersection(&crossing),
+        Some(bounds((10, 0), (16, 5)))
+    );
+    assert!(crossing.overlaps(&right));
+    // Empty bounds contain nothing.
+    let empty = bounds((3, 3), (3, 10));
+    assert!(!empty.contains(Point2d::new(3, 5)));
+    assert_eq!(empty.intersection(&chunk), None);
+    assert!(chunk.contains(Point2d::new(15, 0)));
+    assert!(!chunk.contains(Point2d::new(15, 16)));
+}
+
+#[cfg(test)]
+#[test]
+fn subdivide() {
+    let bounds = |min: (i64, i64), max: (i64, i64)| Bounds {
+        min: Point2d::new(min.0, min.1),
+        max: Point2d::new(max.0, max.1),
+    };
+    let world = bounds((-100, -50), (100, 50));
+    assert_eq!(
+        bounds((-10, 20), (20, 90)).clamp_to(world),
+        bounds((-10, 20), (20, 50))
+    );
+    assert_eq!(bounds((-300, -300), (300, 300)).clamp_to(world), world);
+    let outside = bounds((200, 0), (300, 10)).clamp_to(world);
+    assert_eq!(outside, bounds((100, 0), (100, 10)));
+    assert_eq!(outside.subdivide(Point2d::splat(4)).count(), 0);
+
+    let area = bounds((-7, 3), (10, 12));
+    let pieces: Vec<_> = area.subdivide(Point2d::new(5, 4)).collect();
+    assert_eq!(pieces.len(), 4 * 3);
+    assert_eq!(pieces[0], bounds((-7, 3), (-2, 7)));
+    assert_eq!(pieces[3], bounds((8, 3), (10, 7)));
+    assert_eq!(pieces[11], bounds((8, 11), (10, 12)));
+    let covered: usize = pieces
+        .iter()
+        .map(|p| usize::try_from((p.max.x - p.min.x) * (p.max.y - p.min.y)).unwrap())
+        .sum();
+    assert_eq!(covered, 17 * 9);
+    for (i, a) in pieces.iter().enumerate() {
+        assert!(area.contains_bounds(a));
+        for b in &pieces[i + 1..] {
+            assert!(!a.overlaps(b), "{a:?} {b:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn iter_spiral() {
+    let bounds = |min: (i64, i64), max: (i64, i64)| Bounds {
+        min: Point2d::new(min.0, min.1),
+        max: Point2d::new(max.0, max.1),
+    };
+    for (bounds, center) in [
+        (bounds((-3, -3), (4, 4)), Point2d::splat(0)),
+        (bounds((-3, -3), (4, 4)), Point2d::new(2, -3)),
+        (bounds((0, 0), (21, 2)), Point2d::new(5, 1)),
+        (bounds((-1, 5), (1, 31)), Point2d::new(0, 17)),
+        (Bounds::point(Point2d::splat(7)), Point2d::splat(7)),
+        // The center gets moved into the bounds.
+        (bounds((0, 0), (5, 10)), Point2d::new(-100, 100)),
+    ] {
+        let mut spiral: Vec<_> = bounds.iter_spiral(center).collect();
+        let center = Point2d::new(
+            center.x.clamp(bounds.min.x, bounds.max.x - 1),
+            center.y.clamp(bounds.min.y, bounds.max.y - 1),
+        );
+        let rings: Vec<_> = spiral
+            .iter()
+            .map(|p| (p.x - center.x).abs().max((p.y - center.y).abs()))
+            .collect();
+        assert!(rings.is_sorted(), "{bounds:?} {center:?}: {rings:?}");
+        assert_eq!(spiral[0], center);
+        spiral.sort();
+        let mut all: Vec<_> = bounds.iter().collect();
+        all.sort();
+        assert_eq!(spiral, all, "{bounds:?} {center:?}");
+    }
+    assert_eq!(
+        bounds((1, 1), (0, 3))
+            .iter_spiral(Point2d::splat(0))
+            .count(),
+        0
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn from_points() {
+    assert_eq!(Bounds::<i64>::from_points([]), None);
+    let point = Point2d::new(3, -4);
+    let single = Bounds::from_points([point]).unwrap();
+    assert_eq!(single.max, Point2d::new(4, -3));
+    assert!(single.contains(point));
+    let points = [
+        Point2d::new(3, -4),
+        Point2d::new(-7, 2),
+        Point2d::new(5, 9),
+        Point2d::new(0, 0),
+    ];
+    let bounds = Bounds::from_points(points).unwrap();
+    assert_eq!(
+        bounds,
+        Bounds {
+            min: Point2d::new(-7, -4),
+            max: Point2d::new(6, 10),
+        }
+    );
+    for p in points {
+        assert!(bounds.contains(p), "{p:?}");
+    }
+    assert!(!bounds.contains(Point2d::new(6, 0)));
+    assert!(!bounds.contains(Point2d::new(0, 10)));
+    let mut grown = single;
+    grown.expand_to_include(Point2d::new(-1, 2));
+    assert!(grown.contains(point));
+    assert!(grown.contains(Point2d::new(-1, 2)));
+}
+
+#[cfg(test)]
+#[test]
+fn manhattan_distance_to() {
+    let bounds = Bounds {
+        min: Point2d::new(-10, 0),
+        max: Point2d::new(11, 6),
+    };
+    assert_eq!(bounds.manhattan_distance_to(Point2d::new(0, 2)), 0);
+    assert_eq!(bounds.manhattan_distance_to(Point2d::new(-10, 5)), 0);
+    assert_eq!(bounds.manhattan_distance_to(Point2d::new(3, 0)), 0);
+    assert_eq!(bounds.manhattan_distance_to(Point2d::new(0, -1)), 1);
+    assert_eq!(bounds.manhattan_distance_to(Point2d::new(13, 2)), 3);
+    assert_eq!(bounds.manhattan_distance_to(Point2d::new(-110, 105)), 200);
+    assert_eq!(bounds.manhattan_distance_to(Point2d::new(20, -20)), 30);
+}
+
+#[cfg(test)]
+#[test]
+fn difference() {
+    let old = Bounds {
+        min: Point2d::new(-5, -5),
+        max: Point2d::new(6, 6),
+    };
+    let new = Bounds {
+        min: old.min + Point2d::new(1, 0),
+        max: old.max + Point2d::new(1, 0),
+    };
+    let (load, release) = Bounds::difference(old, new);
+    let load: Vec<_> = load.collect();
+    let release: Vec<_> = release.collect();
+    assert_eq!(
+        load,
+        (-5..=5).map(|y| Point2d::new(6, y)).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        release,
+        (-5..=5).map(|y| Point2d::new(-5, y)).collect::<Vec<_>>()
+    );
+
+    let (load, release) = Bounds::difference(old, old);
+    assert_eq!(load.count() + release.count(), 0);
+
+    let far = Bounds {
+        min: Point2d::splat(100),
+        max: Point2d::splat(102),
+    };
+    let (load, release) = Bounds::difference(old, far);
+    assert_eq!(load.count(), 4);
+    assert_eq!(release.count(), 121);
+
+    let diagonal = Bounds {
+        min: old.min + Point2d::splat(2),
+        max: old.max + Point2d::splat(2),
+    };
+    let (load, release) = Bounds::difference(old, diagonal);
+    let load: Vec<_> = load.collect();
+    let expected: Vec<_> = diagonal.iter().filter(|p| p.x > 5 || p.y > 5).collect();
+    assert_eq!(load.len(), expected.len());
+    assert!(expected.iter().all(|p| load.contains(p)));
+    assert_eq!(release.count(), expected.len());
+}
+
 impl<T: Copy + Num + Add<Output = T> + Sub<Output = T> + DivAssign<T>> Bounds<T> {
     /// The middle point of these bounds.
     pub fn center(&self) -> Point2d<T> {
@@ -435,28 +1422,45 @@ impl<T: Copy + Num + Add<Output = T> + Sub<Output = T> + DivAssign<T>> Bounds<T>
         self.min
     }
 
-    /// Left bottom corner
+    /// Left bottom corner, the last row within the bounds (`max.y` is excluded).
     pub fn left_bottom(&self) -> Point2d<T> {
         Point2d {
             x: self.min.x,
-            y: self.max.y,
+            y: self.max.y - T::ONE,
         }
     }
 
-    /// Right bottom corner
+    /// Right bottom corner, the last position within the bounds (`max` is excluded).
     pub fn right_bottom(&self) -> Point2d<T> {
-        self.max
+        self.max - Point2d::splat(T::ONE)
     }
 
-    /// Right top corner
+    /// Right top corner, the last column within the bounds (`max.x` is excluded).
     pub fn right_top(&self) -> Point2d<T> {
         Point2d {
-            x: self.max.x,
+            x: self.max.x - T::ONE,
             y: self.min.y,
         }
     }
 }
 
+impl<T: Copy + Num + DivAssign<T>> Bounds<T> {
+    /// Split into four quadrants that together cover exactly the same integer points
+    /// (as yielded by [Bounds::iter]), in the order left top, right top, left bottom, right bottom.
+    ///
+    /// If a side has an odd number of points, the left or top quadrants get the extra point.
+    /// If a side has only a single point, the right or bottom quadrants are empty (`min == max`).
+    pub fn split_quadrants(&self) -> [Self; 4] {
+        let mid = self.max - (self.max - self.min) / T::TWO;
+        let x = [(self.min.x, mid.x), (mid.x, self.max.x)];
+        let y = [(self.min.y, mid.y), (mid.y, self.max.y)];
+        [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(xi, yi)| Bounds {
+            min: Point2d::new(x[xi].0, y[yi].0),
+            max: Point2d::new(x[xi].1, y[yi].1),
+        })
+    }
+}
+
 impl<T: Copy + Add<Output = T> + Sub<Output = T>> Bounds<T> {
     /// Add padding on all sides.
     pub fn pad(&self, padding: Point2d<T>) -> Self {
@@ -467,12 +1471,88 @@ impl<T: Copy + Add<Output = T> + Sub<Output = T>> Bounds<T> {
     }
 }
 
+impl Bounds {
+    /// Find all positions within these bounds that are reachable from `start`
+    /// by horizontal and vertical steps onto positions where `is_passable` returns `true`.
+    ///
+    /// The positions are returned in the order they were reached (breadth first),
+    /// so the result is deterministic. Returns nothing if `start` is not passable
+    /// or outside the bounds.
+    pub fn flood_fill(
+        &self,
+        start: Point2d,
+        is_passable: impl Fn(Point2d) -> bool,
+    ) -> Vec<Point2d> {
+        let inside = |p: Point2d| self.contains(p);
+        let mut filled = vec![];
+        if !inside(start) || !is_passable(start) {
+            return filled;
+        }
+        let mut visited = std::collections::HashSet::from([start]);
+        let mut queue = std::collections::VecDeque::from([start]);
+        while let Some(pos) = queue.pop_front() {
+            filled.push(pos);
+            for dir in [
+                Point2d::new(1, 0),
+                Point2d::new(-1, 0),
+                Point2d::new(0, 1),
+                Point2d::new(0, -1),
+            ] {
+                let next = pos + dir;
+                if inside(next) && is_passable(next) && visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        filled
+    }
+
+    /// Add padding on all sides, handling the limits of [i64] as specified by `mode`.
+    #[track_caller]
+    pub const fn pad_with(&self, padding: Point2d, mode: CoordMode) -> Self {
+        Self {
+            min: self.min.sub_with(padding, mode),
+            max: self.max.add_with(padding, mode),
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn coord_modes() {
+    let max = Point2d::new(i64::MAX, 0);
+    let one = Point2d::new(1, 1);
+    assert_eq!(
+        max.add_with(one, CoordMode::Toroidal),
+        Point2d::new(i64::MIN, 1)
+    );
+    assert_eq!(
+        max.add_with(one, CoordMode::Bounded),
+        Point2d::new(i64::MAX, 1)
+    );
+    let bounds = Bounds { min: max, max }.pad_with(one, CoordMode::Bounded);
+    assert_eq!(bounds.min, Point2d::new(i64::MAX - 1, -1));
+    assert_eq!(bounds.max, Point2d::new(i64::MAX, 1));
+    assert_eq!(
+        max.mul_with(Point2d::new(2, 2), CoordMode::Toroidal),
+        Point2d::new(-2, 0)
+    );
+    assert_eq!(max.mul_with(Point2d::new(2, 2), CoordMode::Bounded), max);
+}
+
+#[cfg(all(test, debug_assertions))]
+#[test]
+#[should_panic = "overflow"]
+fn coord_mode_infinite_overflow() {
+    Point2d::new(i64::MAX, 0).add_with(Point2d::new(1, 1), CoordMode::Infinite);
+}
+
 #[cfg(test)]
 #[test]
 fn iter() {
     let grid = Bounds {
         min: Point2d::new(10, 42),
-        max: Point2d::new(12, 43),
+        max: Point2d::new(13, 44),
     };
     let mut iter = grid.iter();
     assert_eq!(iter.next(), Some(grid.min));
@@ -495,6 +1575,313 @@ fn iter_point() {
     assert_eq!(iter.next(), None);
 }
 
+#[cfg(test)]
+#[test]
+fn half_open() {
+    let p = Point2d::new(-3, 7);
+    let point = Bounds::point(p);
+    assert!(point.contains(p));
+    assert!(!point.is_empty());
+    assert_eq!(point.intersection(&point), Some(point));
+    assert_eq!(point.manhattan_distance_to(p), 0);
+    let bounds = |min: (i64, i64), max: (i64, i64)| Bounds {
+        min: Point2d::new(min.0, min.1),
+        max: Point2d::new(max.0, max.1),
+    };
+    for bounds in [
+        point,
+        bounds((0, 0), (4, 3)),
+        bounds((-5, 2), (-4, 6)),
+        // Empty
+        bounds((2, 2), (2, 5)),
+        bounds((0, 3), (4, 3)),
+        bounds((3, 3), (1, 1)),
+    ] {
+        let around: Vec<_> = bounds.pad(Point2d::splat(2)).iter().collect();
+        let inside: Vec<_> = around
+            .iter()
+            .copied()
+            .filter(|&p| bounds.contains(p))
+            .collect();
+        assert_eq!(bounds.iter().collect::<Vec<_>>(), inside, "{bounds:?}");
+        assert_eq!(bounds.is_empty(), inside.is_empty(), "{bounds:?}");
+        assert!(
+            bounds.iter_border().all(|p| bounds.contains(p)),
+            "{bounds:?}"
+        );
+        if !bounds.is_empty() {
+            for p in around {
+                assert_eq!(
+                    bounds.manhattan_distance_to(p) == 0,
+                    bounds.contains(p),
+                    "{bounds:?} {p:?}"
+                );
+            }
+            for corner in [
+                bounds.left_top(),
+                bounds.left_bottom(),
+                bounds.right_bottom(),
+                bounds.right_top(),
+            ] {
+                assert!(bounds.contains(corner), "{bounds:?} {corner:?}");
+            }
+        }
+    }
+    let line = Point2d::new(4, -1).to(Point2d::new(-2, 3));
+    assert!(line.bounds().contains(line.start));
+    assert!(line.bounds().contains(line.end));
+    assert_eq!(line.bounds(), bounds((-2, -1), (5, 4)));
+}
+
+#[cfg(test)]
+#[test]
+fn split_quadrants() {
+    for (w, h) in [(1, 1), (1, 4), (2, 2), (5, 3), (8, 7)] {
+        let bounds = Bounds {
+            min: Point2d::new(-3, 10),
+            max: Point2d::new(-3 + w, 10 + h),
+        };
+        let mut expected: Vec<_> = bounds.iter().collect();
+        let mut quadrants: Vec<_> = bounds
+            .split_quadrants()
+            .into_iter()
+            .flat_map(|q| q.iter())
+            .collect();
+        expected.sort();
+        quadrants.sort();
+        assert_eq!(quadrants, expected, "{w}x{h}");
+    }
+    let [lt, rt, lb, rb] = Bounds {
+        min: Point2d::new(0, 0),
+        max: Point2d::new(5, 3),
+    }
+    .split_quadrants();
+    assert_eq!(lt.max, Point2d::new(3, 2));
+    assert_eq!(rt.min, Point2d::new(3, 0));
+    assert_eq!(lb.min, Point2d::new(0, 2));
+    assert_eq!(rb.max, Point2d::new(5, 3));
+}
+
+#[cfg(test)]
+#[test]
+fn flood_fill() {
+    let bounds = Bounds {
+        min: Point2d::new(0, 0),
+        max: Point2d::new(10, 10),
+    };
+    // Unbounded open space is only filled within the bounds.
+    let all = bounds.flood_fill(Point2d::new(3, 3), |_| true);
+    assert_eq!(all.len(), 100);
+    assert_eq!(all[0], Point2d::new(3, 3));
+    assert!(all.iter().all(|&p| bounds.iter().any(|q| q == p)));
+
+    // A vertical wall at x == 5 with a gap at y == 8 and a closed room in the top right
+    let wall = |p: Point2d| p.x == 5 && p.y != 8 || (p.x == 7 && p.y <= 2) || (p.x > 7 && p.y == 2);
+    let left = bounds.flood_fill(Point2d::new(0, 0), |p| !wall(p));
+    let room = bounds.flood_fill(Point2d::new(9, 0), |p| !wall(p));
+    assert_eq!(room.len(), 4);
+    assert!(room.iter().all(|p| p.x > 7 && p.y < 2));
+    assert_eq!(left.len(), 100 - 9 - 3 - 2 - room.len());
+    assert!(left.contains(&Point2d::new(9, 9)));
+    assert!(left.iter().all(|&p| !wall(p)));
+    assert_eq!(left, bounds.flood_fill(Point2d::new(0, 0), |p| !wall(p)));
+
+    assert!(
+        bounds
+            .flood_fill(Point2d::new(5, 0), |p| !wall(p))
+            .is_empty()
+    );
+    assert!(bounds.flood_fill(Point2d::new(-1, 0), |_| true).is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn lerp() {
+    let a = Point2d::new(-10, 4);
+    let b = Point2d::new(20, 9);
+    assert_eq!(a.lerp(b, 0.), a);
+    assert_eq!(a.lerp(b, 1.), b);
+    assert_eq!(a.lerp(b, 0.5), Point2d::new(5, 7));
+    let line = a.to(b);
+    assert_eq!(line.point_at(0.), a);
+    assert_eq!(line.point_at(1.), b);
+    assert_eq!(line.point_at(0.5), Point2d::new(5, 7));
+    assert_eq!(line.flip().point_at(0.5), Point2d::new(5, 7));
+    // The difference doesn't fit into an `i64`.
+    let (min, max) = (Point2d::splat(i64::MIN), Point2d::splat(i64::MAX));
+    assert_eq!(min.lerp(max, 0.), min);
+    assert_eq!(min.lerp(max, 0.5), Point2d::splat(0));
+    assert_eq!(min.lerp(max, 1.), max);
+    assert_eq!(max.lerp(min, 1.), min);
+}
+
+#[cfg(test)]
+#[test]
+fn canonicalize() {
+    let a = Point2d::new(5, -3);
+    let b = Point2d::new(-2, 10);
+    assert_eq!(a.to(b).canonicalize(), b.to(a).canonicalize());
+    assert_eq!(b.to(a).canonicalize(), b.to(a));
+    assert!(a.to(b).same_segment(&b.to(a)));
+    assert!(!a.to(b).same_segment(&a.to(a)));
+    // Vertical lines are ordered by y
+    let c = Point2d::new(5, 7);
+    assert_eq!(c.to(a).canonicalize(), a.to(c));
+}
+
+#[cfg(test)]
+#[test]
+fn clip() {
+    let bounds = Bounds {
+        min: Point2d::new(0, 0),
+        max: Point2d::new(10, 10),
+    };
+    let line = |a: (i64, i64), b: (i64, i64)| Point2d::new(a.0, a.1).to(Point2d::new(b.0, b.1));
+    assert_eq!(
+        line((2, 3), (4, 5)).clip(bounds),
+        Some(line((2, 3), (4, 5)))
+    );
+    assert_eq!(
+        line((-5, 5), (15, 5)).clip(bounds),
+        Some(line((0, 5), (10, 5)))
+    );
+    assert_eq!(
+        line((5, 15), (5, -5)).clip(bounds),
+        Some(line((5, 10), (5, 0)))
+    );
+    assert_eq!(
+        line((-5, -5), (5, 5)).clip(bounds),
+        Some(line((0, 0), (5, 5)))
+    );
+    assert_eq!(
+        line((-5, 5), (5, -5)).clip(bounds),
+        Some(line((0, 0), (0, 0)))
+    );
+    assert_eq!(line((-5, 4), (4, -5)).clip(bounds), None);
+    assert_eq!(line((11, 0), (11, 10)).clip(bounds), None);
+
+    // The differences of these coordinates overflow an i64.
+    let (lo, hi) = (i64::MIN, i64::MAX);
+    let everything = Bounds {
+        min: Point2d::splat(lo),
+        max: Point2d::splat(hi),
+    };
+    assert_eq!(
+        line((lo, lo), (hi, hi)).clip(everything),
+        Some(line((lo, lo), (hi, hi)))
+    );
+    assert_eq!(
+        line((lo, 5), (hi, 5)).clip(bounds),
+        Some(line((0, 5), (10, 5)))
+    );
+    assert_eq!(line((hi, lo), (hi, hi)).clip(bounds), None);
+    assert_eq!(
+        line((-5, 5), (15, 5)).clip(everything),
+        Some(line((-5, 5), (15, 5)))
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn intersection() {
+    let line = |a: (i64, i64), b: (i64, i64)| Point2d::new(a.0, a.1).to(Point2d::new(b.0, b.1));
+    let p = |x, y| Some(Point2d::new(x, y));
+    assert_eq!(
+        line((0, 0), (10, 10)).intersection(&line((0, 10), (10, 0))),
+        p(5, 5)
+    );
+    // Rounded to the nearest integer position.
+    assert_eq!(
+        line((0, 0), (3, 0)).intersection(&line((1, -1), (2, 1))),
+        p(2, 0)
+    );
+    // The infinite lines cross, but the segments don't.
+    assert_eq!(
+        line((0, 0), (4, 4)).intersection(&line((0, 10), (10, 0))),
+        None
+    );
+    // Touching at an end point.
+    assert_eq!(
+        line((0, 0), (5, 5)).intersection(&line((5, 5), (9, 0))),
+        p(5, 5)
+    );
+    assert_eq!(
+        line((0, 0), (10, 0)).intersection(&line((5, 0), (5, 7))),
+        p(5, 0)
+    );
+    // Parallel.
+    assert_eq!(
+        line((0, 0), (10, 0)).intersection(&line((0, 1), (10, 1))),
+        None
+    );
+    // Collinear, reporting the shared point closest to `self.start`.
+    assert_eq!(
+        line((0, 0), (10, 0)).intersection(&line((5, 0), (20, 0))),
+        p(5, 0)
+    );
+    assert_eq!(
+        line((10, 0), (0, 0)).intersection(&line((5, 0), (20, 0))),
+        p(10, 0)
+    );
+    assert_eq!(
+        line((3, 3), (6, 6)).intersection(&line((0, 0), (9, 9))),
+        p(3, 3)
+    );
+    assert_eq!(
+        line((0, 0), (4, 4)).intersection(&line((6, 6), (9, 9))),
+        None
+    );
+    assert_eq!(
+        line((0, 0), (4, 4)).intersection(&line((4, 4), (9, 9))),
+        p(4, 4)
+    );
+    // Degenerate segments.
+    assert_eq!(
+        line((2, 2), (2, 2)).intersection(&line((0, 0), (4, 4))),
+        p(2, 2)
+    );
+    assert_eq!(
+        line((0, 0), (4, 4)).intersection(&line((2, 3), (2, 3))),
+        None
+    );
+    // Far apart from the origin, where the cross products overflow `i64`.
+    let far = i64::MAX / 2;
+    assert_eq!(
+        line((far, far), (far + (1 << 40), far + (1 << 40)))
+            .intersection(&line((far, far + (1 << 40)), (far + (1 << 40), far))),
+        p(far + (1 << 39), far + (1 << 39))
+    );
+    // Near the edges of the world, where the differences don't fit into an `i64` either.
+    let (min, max) = (i64::MIN, i64::MAX);
+    assert_eq!(
+        line((min, 0), (max, 0)).intersection(&line((0, min), (0, max))),
+        p(0, 0)
+    );
+    assert_eq!(
+        line((min, min), (max, max)).intersection(&line((min, max), (max, min))),
+        p(0, 0)
+    );
+    assert_eq!(
+        line((max - 10, max), (max, max - 10))
+            .intersection(&line((max - 10, max - 10), (max, max))),
+        p(max - 5, max - 5)
+    );
+    assert_eq!(
+        line((min, min), (max, max - 1)).intersection(&line((min, min + 1), (max, max))),
+        None
+    );
+    assert_eq!(
+        line((min, 0), (max, 0)).intersection(&line((max, 0), (max, max))),
+        p(max, 0)
+    );
+    assert_eq!(
+        line((min, min), (max, max)).intersection(&line((min + 2, min + 2), (0, 0))),
+        p(min + 2, min + 2)
+    );
+    assert!(line((0, 0), (10, 10)).intersects(&line((0, 10), (10, 0))));
+    assert!(!line((0, 0), (10, 0)).intersects(&line((0, 1), (10, 1))));
+}
+
 impl<T: DivAssign + Copy> Div<Point2d<T>> for Bounds<T> {
     type Output = Self;
     fn div(mut self, rhs: Point2d<T>) -> Self::Output {
@@ -543,3 +1930,35 @@ impl Num for i64 {
         self as u64
     }
 }
+
+#[cfg(test)]
+#[test]
+fn chebyshev_dist() {
+    let a = Point2d::new(-3, 10);
+    assert_eq!(a.chebyshev_dist(Point2d::new(4, 8)), 7);
+    assert_eq!(a.chebyshev_dist(Point2d::new(-5, -10)), 20);
+    assert_eq!(a.chebyshev_dist(a), 0);
+    let corner = Point2d::splat(i64::MIN);
+    assert_eq!(corner.chebyshev_dist(Point2d::new(i64::MAX, 0)), u64::MAX);
+}
+
+#[cfg(test)]
+#[test]
+fn within_radius() {
+    let a = Point2d::new(0, 0);
+    assert!(a.within_radius(Point2d::new(3, 4), 5));
+    assert!(!a.within_radius(Point2d::new(3, 4), 4));
+    assert!(a.within_radius(a, 0));
+    assert!(!a.within_radius(a, -1));
+
+    // `dist_squared` would overflow for these.
+    let far = Point2d::splat(i64::MAX / 2);
+    let near = far - Point2d::new(4_000_000_000, 3_000_000_000);
+    assert!(far.x.checked_sub(near.x).unwrap().checked_pow(2).is_none());
+    assert!(far.within_radius(near, 5_000_000_000));
+    assert!(!far.within_radius(near, 4_999_999_999));
+    let opposite = Point2d::splat(-i64::MAX / 2);
+    assert!(!far.within_radius(opposite, i64::MAX));
+    assert!(far.within_radius(Point2d::new(-i64::MAX / 2, far.y), i64::MAX));
+    assert!(!Point2d::splat(i64::MAX).within_radius(Point2d::splat(i64::MIN), i64::MAX));
+}
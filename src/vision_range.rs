@@ -0,0 +1,73 @@
+use crate::{Chunk, ChunkExt as _, GridBounds, vec2::Bounds, vec2::Point2d};
+
+/// The area around a point of interest (e.g. a camera) whose chunks need to be loaded.
+///
+/// Handles fractional positions (e.g. for sub-pixel camera movement) and zoom levels
+/// by always rounding outwards, so everything that is visible is within the computed bounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisionRange {
+    /// The center of the visible area in world coordinates.
+    pub center: Point2d<f64>,
+    /// Half the width and height of the visible area in world coordinates.
+    pub half_extent: Point2d<f64>,
+    /// Additional world coordinates to load around the visible area,
+    /// e.g. because things outside of it can reach into it.
+    pub padding: Point2d,
+}
+
+impl VisionRange {
+    /// Visible area at `center`, reaching `half_extent` in each direction.
+    pub fn new(center: Point2d<f64>, half_extent: Point2d<f64>) -> Self {
+        Self {
+            center,
+            half_extent: Point2d::new(half_extent.x.abs(), half_extent.y.abs()),
+            padding: Point2d::splat(0),
+        }
+    }
+
+    /// Zoom in (`zoom > 1`) or out (`zoom < 1`), scaling the visible area
+    /// around its center.
+    pub fn with_zoom(self, zoom: f64) -> Self {
+        assert!(zoom > 0.0, "zoom must be positive, not {zoom}");
+        Self {
+            half_extent: Point2d::new(self.half_extent.x / zoom, self.half_extent.y / zoom),
+            ..self
+        }
+    }
+
+    /// Add padding to the area to load.
+    pub fn with_padding(self, padding: Point2d) -> Self {
+        Self {
+            padding: self.padding + padding,
+            ..self
+        }
+    }
+
+    /// The visible area plus the padding, in world coordinates.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "coordinates outside of i64 can't be loaded anyway"
+    )]
+    pub fn bounds(&self) -> Bounds {
+        let min = Point2d::new(
+            (self.center.x - self.half_extent.x).floor() as i64,
+            (self.center.y - self.half_extent.y).floor() as i64,
+        );
+        let max = Point2d::new(
+            (self.center.x + self.half_extent.x).ceil() as i64,
+            (self.center.y + self.half_extent.y).ceil() as i64,
+        );
+        Bounds { min, max }.pad(self.padding)
+    }
+
+    /// The world coordinates a layer of `C` chunks needs to have loaded for the visible area.
+    /// Same as [VisionRange::bounds], but additionally padded via [ChunkExt::vision_range](crate::ChunkExt::vision_range).
+    pub fn bounds_for<C: Chunk>(&self) -> Bounds {
+        C::vision_range(self.bounds())
+    }
+
+    /// The chunks of a `C` layer that need to be loaded for the visible area.
+    pub fn grid_bounds<C: Chunk>(&self) -> GridBounds<C> {
+        C::covering_grid_bounds(self.bounds_for::<C>())
+    }
+}
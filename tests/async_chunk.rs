@@ -0,0 +1,108 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+use layer_proc_gen::*;
+use vec2::{Bounds, Point2d};
+
+thread_local! {
+    static EVENTS: RefCell<Vec<(&'static str, Point2d)>> = const { RefCell::new(vec![]) };
+}
+
+#[derive(Clone, Default, PartialEq, Debug)]
+struct Fetched(Point2d);
+
+impl Chunk for Fetched {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        Fetched(index.map(|i| i.0))
+    }
+}
+
+impl AsyncChunk for Fetched {
+    async fn compute_async(_layer: &Self::Dependencies, index: GridPoint<Self>) -> Self {
+        let index = index.map(|i| i.0);
+        EVENTS.with_borrow_mut(|events| events.push(("start", index)));
+        // Stand-in for waiting on a file or network request.
+        YieldNow(false).await;
+        EVENTS.with_borrow_mut(|events| events.push(("end", index)));
+        Fetched(index)
+    }
+}
+
+/// Returns `Pending` once, like `tokio::task::yield_now`.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<T>(future: impl Future<Output = T>) -> T {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+            return value;
+        }
+        std::thread::park();
+    }
+}
+
+#[test]
+fn computes_concurrently() {
+    let layer = Layer::<Fetched>::default();
+    let bounds = Bounds {
+        min: Point2d::splat(0),
+        max: Point2d::new(300, 100),
+    };
+    let grid = Fetched::bounds_to_grid(bounds);
+    // Already cached chunks are not computed again.
+    layer.get(grid.min);
+    block_on(layer.ensure_loaded_in_bounds_async(bounds));
+    assert!(layer.is_loaded_in_grid_bounds(grid));
+    for index in grid.iter() {
+        assert_eq!(layer.grid().peek(index), Some(Fetched(index.map(|i| i.0))));
+    }
+
+    let events = EVENTS.take();
+    let total = grid.iter().count() - 1;
+    assert_eq!(events.len(), total * 2);
+    // All chunks started waiting before the first one finished.
+    assert!(events[..total].iter().all(|&(event, _)| event == "start"));
+    assert!(
+        !events
+            .iter()
+            .any(|&(_, index)| index == grid.min.map(|i| i.0))
+    );
+}
@@ -0,0 +1,71 @@
+//! Checking that clearing a layer also clears all the dependency chunks it loaded.
+
+use layer_proc_gen::*;
+use vec2::{Bounds, Point2d};
+
+#[path = "../examples/common/mod.rs"]
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+#[test]
+fn clearing_roads_clears_everything() {
+    let roads = Layer::<Roads>::new(RoadsDeps {
+        intersections: Layer::default(),
+    });
+    let area = Bounds {
+        min: Point2d::new(-100, -50),
+        max: Point2d::new(60, 130),
+    };
+    roads.ensure_loaded_in_bounds(area);
+    roads.clear(area);
+    roads.assert_balanced();
+}
+
+#[derive(Clone, Default)]
+struct Base;
+
+impl Chunk for Base {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(_: &Self::Dependencies, _: GridPoint<Self>, _bounds: Bounds) -> Self {
+        Base
+    }
+}
+
+impl debug::Debug for Base {}
+
+/// Forgets to clear the [Base] chunk it was computed from.
+#[derive(Clone, Default)]
+struct Leaky;
+
+impl Chunk for Leaky {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Layer<Base>;
+
+    fn compute_in_bounds(
+        base: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        base.get(index.same_index());
+        Leaky
+    }
+
+    fn clear(_: &Self::Dependencies, _: GridPoint<Self>) {}
+}
+
+impl debug::Debug for Leaky {}
+
+#[test]
+#[should_panic = "chunks still cached: Base (1 chunks"]
+fn forgotten_clear() {
+    let layer = Layer::<Leaky>::new(Layer::new(()));
+    let index = point!(2, -1);
+    layer.get(index);
+    layer.clear(Leaky::bounds(index));
+    layer.assert_balanced();
+}
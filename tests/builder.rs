@@ -0,0 +1,131 @@
+//! Creating layers together with their dependencies via a [LayerBuilder].
+
+use builder::{BuildError, LayerBuilder};
+use layer_proc_gen::*;
+
+#[path = "../examples/common/mod.rs"]
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+#[test]
+fn same_as_manual_wiring() {
+    let manual = Layer::<Roads>::new(RoadsDeps {
+        intersections: Layer::new(ReducedLocationsDeps {
+            intersections: Layer::new(Layer::new(Seed(7))),
+            cities: Layer::new(Layer::new(Seed(7))),
+        }),
+    });
+
+    let mut builder = LayerBuilder::new().with(Seed(7));
+    let locations = builder.share::<ReducedLocations>().unwrap();
+    let roads = builder.build::<Roads>().unwrap();
+    for index in [point!(0, 0), point!(-3, 5)] {
+        assert_eq!(roads.get(index), manual.get(index));
+    }
+
+    // The roads use the shared locations layer instead of their own.
+    let before = locations.loaded().count();
+    roads.get(point!(10, 10));
+    assert!(locations.loaded().count() > before);
+    let other_roads = builder.build::<Roads>().unwrap();
+    assert_eq!(
+        other_roads.get_with_freshness(point!(0, 0)).1,
+        Freshness::Created
+    );
+    assert_eq!(
+        other_roads.intersections.get_with_freshness(point!(0, 0)).1,
+        Freshness::Cached
+    );
+}
+
+#[test]
+fn missing_seed() {
+    let err = LayerBuilder::new().build::<Roads>().err().unwrap();
+    let BuildError::Missing {
+        dependency,
+        needed_by: Some(needed_by),
+    } = err.clone()
+    else {
+        panic!("{err}")
+    };
+    assert_eq!(dependency, std::any::type_name::<Seed>());
+    assert!(needed_by.contains("UniformPoint"), "{needed_by}");
+    assert!(err.to_string().contains("was not registered"));
+    assert_eq!(
+        LayerBuilder::new().require::<Seed>(),
+        Err(BuildError::Missing {
+            dependency: std::any::type_name::<Seed>(),
+            needed_by: None
+        })
+    );
+}
+
+/// Dependencies that the builder knows nothing about.
+#[derive(Clone, Default)]
+struct Custom;
+
+impl Dependencies for Custom {
+    const HAS_LAYERS: bool = false;
+
+    fn debug(&self) -> Vec<&dyn debug::DynLayer> {
+        vec![]
+    }
+}
+
+#[derive(Clone, Default)]
+struct Unbuildable;
+
+impl Chunk for Unbuildable {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Custom;
+
+    fn compute_in_bounds(_: &Custom, _: GridPoint<Self>, _bounds: Bounds) -> Self {
+        Unbuildable
+    }
+}
+
+impl debug::Debug for Unbuildable {}
+
+// Compiles even though these can't be built by a `LayerBuilder`.
+deps! {
+    struct UnbuildableDeps {
+        inner: Unbuildable,
+    }
+}
+
+#[test]
+fn registered_layers_need_no_build() {
+    #[derive(Clone, Default)]
+    struct Outer;
+
+    impl Chunk for Outer {
+        type LayerStore<T> = std::sync::Arc<T>;
+        type Grid = RollingGrid<Self>;
+        type Dependencies = UnbuildableDeps;
+
+        fn compute_in_bounds(
+            deps: &Self::Dependencies,
+            index: GridPoint<Self>,
+            _bounds: Bounds,
+        ) -> Self {
+            deps.inner.get(index.same_index());
+            Outer
+        }
+
+        fn clear(deps: &Self::Dependencies, index: GridPoint<Self>) {
+            deps.inner.clear(Self::bounds(index));
+        }
+    }
+
+    impl debug::Debug for Outer {}
+
+    let outer = Layer::<Outer>::new(UnbuildableDeps {
+        inner: Layer::new(Custom),
+    });
+    outer.get(point!(1, 1));
+    let builder = LayerBuilder::new().with(outer);
+    assert!(builder.get::<Layer<Outer>>().is_some());
+    assert!(builder.get::<Layer<Roads>>().is_none());
+}
@@ -0,0 +1,140 @@
+//! Randomized checks of the invariants of the coordinate math, including
+//! negative coordinates and positions close to the limits of `i64`.
+
+use layer_proc_gen::*;
+use rand::prelude::*;
+use vec2::{Bounds, Point2d};
+
+#[derive(Clone, Default)]
+struct Square;
+
+impl Chunk for Square {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        Square
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[derive(Clone, Default)]
+struct Oblong;
+
+impl Chunk for Oblong {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+    const SIZE: Point2d<u8> = Point2d::new(3, 11);
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        Oblong
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+const ITERATIONS: usize = 10_000;
+
+/// Mostly values around zero (where the sign changes), but also anywhere
+/// in the range of `i64`, except for the last chunk where `bounds.max` overflows.
+fn coordinate(rng: &mut impl Rng) -> i64 {
+    let max = i64::MAX - (1 << 11);
+    match rng.random_range(0..4) {
+        0 => rng.random_range(-1000..1000),
+        1 => rng.random_range(i64::MIN..=i64::MIN + 1000),
+        2 => rng.random_range(max - 1000..=max),
+        _ => rng.random_range(i64::MIN..=max),
+    }
+}
+
+fn point(rng: &mut impl Rng) -> Point2d {
+    Point2d::new(coordinate(rng), coordinate(rng))
+}
+
+fn chunk_invariants<C: Chunk>() {
+    let mut rng = SmallRng::seed_from_u64(0);
+    let size = C::SIZE.map(|i| 1_i64 << i);
+    for _ in 0..ITERATIONS {
+        let pos = point(&mut rng);
+        let index = C::pos_to_grid(pos);
+        let local = C::pos_within_chunk(pos);
+        let bounds = C::bounds(index);
+        assert!((0..size.x).contains(&local.x), "{pos:?}: {local:?}");
+        assert!((0..size.y).contains(&local.y), "{pos:?}: {local:?}");
+        assert_eq!(bounds.min + local, pos);
+        assert!(
+            (bounds.min.x..bounds.max.x).contains(&pos.x)
+                && (bounds.min.y..bounds.max.y).contains(&pos.y),
+            "{pos:?} not in {bounds:?}"
+        );
+        assert_eq!(C::pos_to_grid(bounds.min), index);
+        assert_eq!(C::pos_to_grid(bounds.max - Point2d::splat(1)), index);
+        assert_eq!(C::covering_grid_bounds(bounds), Bounds::point(index));
+        assert_eq!(C::bounds_to_grid(bounds), Bounds::point(index));
+    }
+}
+
+#[test]
+fn square_chunk_invariants() {
+    chunk_invariants::<Square>();
+}
+
+#[test]
+fn oblong_chunk_invariants() {
+    chunk_invariants::<Oblong>();
+}
+
+#[test]
+fn grid_bounds_contain_all_positions() {
+    let mut rng = SmallRng::seed_from_u64(1);
+    for _ in 0..ITERATIONS {
+        let min = point(&mut rng);
+        let size = Point2d::new(rng.random_range(1..2000), rng.random_range(1..2000));
+        let bounds = Bounds {
+            min,
+            max: min + size,
+        };
+        let grid = Oblong::covering_grid_bounds(bounds);
+        let touched = Oblong::bounds_to_grid(bounds);
+        for pos in [
+            bounds.min,
+            bounds.max - Point2d::splat(1),
+            min + Point2d::new(rng.random_range(0..size.x), rng.random_range(0..size.y)),
+        ] {
+            let index = Oblong::pos_to_grid(pos);
+            assert!(grid.contains(index), "{pos:?} {grid:?}");
+            assert!(touched.contains(index), "{pos:?} {touched:?}");
+        }
+    }
+}
+
+#[test]
+fn iter_visits_every_point_once() {
+    let mut rng = SmallRng::seed_from_u64(2);
+    for _ in 0..1000 {
+        let min = point(&mut rng);
+        let size = Point2d::new(rng.random_range(-3..10), rng.random_range(-3..10));
+        let bounds = Bounds {
+            min,
+            max: min + size,
+        };
+        let points: Vec<_> = bounds.iter().collect();
+        let expected = usize::try_from(size.x.max(0) * size.y.max(0)).unwrap();
+        assert_eq!(points.len(), expected, "{bounds:?}");
+        for p in points {
+            assert!(bounds.min.x <= p.x && p.x < bounds.max.x);
+            assert!(bounds.min.y <= p.y && p.y < bounds.max.y);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::{cell::OnceCell, rc::Rc};
+
+use layer_proc_gen::*;
+use vec2::Point2d;
+
+/// Dependencies that can be tied into a knot after creating the layers.
+#[derive(Clone, Default)]
+struct Knot(Rc<OnceCell<Layer<B>>>);
+
+impl Dependencies for Knot {
+    fn debug(&self) -> Vec<&dyn debug::DynLayer> {
+        vec![]
+    }
+}
+
+#[derive(Clone, Default)]
+struct A;
+
+impl Chunk for A {
+    type LayerStore<T> = Rc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Knot;
+
+    fn compute_in_bounds(
+        layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        layer.0.get().unwrap().get(index.same_index());
+        A
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+impl debug::Debug for A {}
+
+#[derive(Clone, Default)]
+struct B;
+
+impl Chunk for B {
+    type LayerStore<T> = Rc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Layer<A>;
+
+    fn compute_in_bounds(
+        layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        layer.get(index.same_index());
+        B
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    should_panic = "dependency cycle: A (1, -2) -> B (1, -2) -> A (1, -2)"
+)]
+#[cfg_attr(not(debug_assertions), ignore = "only detected with debug assertions")]
+fn two_layer_cycle() {
+    let knot = Knot::default();
+    let a = Layer::<A>::new(knot.clone());
+    let b = Layer::<B>::new(a.clone());
+    assert!(knot.0.set(b).is_ok());
+    a.ensure_loaded_in_bounds(A::bounds(Point2d::new(1, -2).map(GridIndex::from_raw)));
+}
+
+/// Dependencies that can point back at the layer using them.
+#[derive(Clone, Default)]
+struct Tail(Rc<OnceCell<Layer<Ouroboros>>>);
+
+impl Dependencies for Tail {
+    fn debug(&self) -> Vec<&dyn debug::DynLayer> {
+        vec![]
+    }
+}
+
+/// Needs its own chunk to compute itself.
+#[derive(Clone, Default)]
+struct Ouroboros;
+
+impl Chunk for Ouroboros {
+    type LayerStore<T> = Rc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Tail;
+
+    fn compute_in_bounds(
+        layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        layer.0.get().unwrap().get(index);
+        Ouroboros
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    should_panic = "dependency cycle: Ouroboros (3, 4) -> Ouroboros (3, 4)"
+)]
+#[cfg_attr(not(debug_assertions), ignore = "only detected with debug assertions")]
+fn shallow_cycle() {
+    let tail = Tail::default();
+    let layer = Layer::<Ouroboros>::new(tail.clone());
+    assert!(tail.0.set(layer.clone()).is_ok());
+    layer.get_shallow(point!(3, 4));
+}
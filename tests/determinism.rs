@@ -0,0 +1,71 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    process::Command,
+};
+
+use layer_proc_gen::*;
+use rng::ChunkRng;
+use vec2::{Bounds, Point2d};
+
+#[derive(Clone, Default)]
+struct Scattered(Vec<Point2d>);
+
+impl Chunk for Scattered {
+    type LayerStore<T> = T;
+    type Grid = SparseGrid<Self>;
+    type Dependencies = Seed;
+
+    fn compute_in_bounds(
+        seed: &Self::Dependencies,
+        index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        let mut rng = ChunkRng::new::<0, _>(index, *seed).stream(0);
+        Scattered((0..5).map(|_| bounds.sample(&mut rng)).collect())
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+/// Hash a region of chunks in the order the cache iterates over them.
+fn content_hash() -> u64 {
+    let layer = Layer::<Scattered>::new(Seed(42));
+    layer.ensure_loaded_in_bounds(Bounds {
+        min: Point2d::splat(-2000),
+        max: Point2d::splat(2000),
+    });
+    let mut hasher = DefaultHasher::new();
+    for (index, chunk) in layer.grid().iter_all_loaded() {
+        index.map(|i| i.0).hash(&mut hasher);
+        chunk.0.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+const CHILD_ENV: &str = "LAYER_PROC_GEN_DETERMINISM_CHILD";
+
+#[test]
+fn identical_across_processes() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        println!("content hash: {}", content_hash());
+        return;
+    }
+    let run = || {
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args(["identical_across_processes", "--exact", "--nocapture"])
+            .env(CHILD_ENV, "1")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        stdout
+            .lines()
+            .find_map(|line| Some(line.split_once("content hash: ")?.1))
+            .unwrap_or_else(|| panic!("no hash in output:\n{stdout}"))
+            .parse::<u64>()
+            .unwrap()
+    };
+    let hash = content_hash();
+    assert_eq!(run(), hash);
+    assert_eq!(run(), hash);
+}
@@ -0,0 +1,173 @@
+//! Chunks holding differently typed features behind trait objects.
+
+use std::sync::Arc;
+
+use debug::{Debug, DebugContent};
+use layer_proc_gen::*;
+use rng::ChunkRng;
+use vec2::{Bounds, Point2d};
+
+trait Feature {
+    fn position(&self) -> Point2d;
+    fn debug(&self) -> DebugContent;
+}
+
+struct Tree(Point2d);
+
+impl Feature for Tree {
+    fn position(&self) -> Point2d {
+        self.0
+    }
+
+    fn debug(&self) -> DebugContent {
+        DebugContent::Circle {
+            center: self.0,
+            radius: 2.0,
+        }
+    }
+}
+
+struct Building {
+    pos: Point2d,
+    name: String,
+}
+
+impl Feature for Building {
+    fn position(&self) -> Point2d {
+        self.pos
+    }
+
+    fn debug(&self) -> DebugContent {
+        DebugContent::Text {
+            pos: self.pos,
+            label: self.name.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct Features(Arc<[Box<dyn Feature>]>);
+
+impl Chunk for Features {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Seed;
+
+    fn compute_in_bounds(
+        seed: &Self::Dependencies,
+        index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        let mut rng = ChunkRng::new::<0, _>(index, *seed).stream(0);
+        let mut features: Vec<Box<dyn Feature>> = (0..3)
+            .map(|_| Box::new(Tree(bounds.sample(&mut rng))) as Box<dyn Feature>)
+            .collect();
+        features.push(Box::new(Building {
+            pos: bounds.sample(&mut rng),
+            name: format!("house {index}"),
+        }));
+        Features(features.into())
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+impl Debug for Features {
+    fn debug(&self, _bounds: Bounds) -> Vec<DebugContent> {
+        self.0.iter().map(|feature| feature.debug()).collect()
+    }
+}
+
+#[test]
+fn mixed_features() {
+    let layer = Layer::<Features>::new(Seed(7));
+    let index = point!(2, -1);
+    let chunk = layer.get(index);
+    assert_eq!(chunk.0.len(), 4);
+    let bounds = Features::bounds(index);
+    for feature in chunk.0.iter() {
+        let pos = feature.position();
+        assert_eq!(
+            Features::pos_to_grid(pos),
+            index,
+            "{pos:?} outside of {bounds:?}"
+        );
+    }
+
+    let debug = chunk.debug(bounds);
+    let circles = debug
+        .iter()
+        .filter(|c| matches!(c, DebugContent::Circle { .. }))
+        .count();
+    let labels: Vec<_> = debug
+        .iter()
+        .filter_map(|c| match c {
+            DebugContent::Text { label, .. } => Some(label.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(circles, 3);
+    assert_eq!(labels.len(), 1);
+    assert!(labels[0].starts_with("house (2, -1)"), "{labels:?}");
+
+    // Reading the chunk again shares the features instead of regenerating them.
+    assert!(Arc::ptr_eq(&layer.get(index).0, &chunk.0));
+}
+
+/// A triangular lake in the middle of each chunk.
+#[derive(Clone, Default)]
+struct Lake;
+
+impl Chunk for Lake {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        Lake
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+impl Debug for Lake {
+    fn debug(&self, bounds: Bounds) -> Vec<DebugContent> {
+        let center = bounds.center();
+        vec![DebugContent::Polygon {
+            points: vec![
+                center + Point2d::new(0, -10),
+                center + Point2d::new(10, 10),
+                center + Point2d::new(-10, 10),
+            ],
+            fill: true,
+        }]
+    }
+}
+
+#[test]
+fn polygon() {
+    let layer = Layer::<Lake>::new(());
+    let index = point!(-3, 4);
+    layer.get(index);
+    let bounds = Lake::bounds(index);
+    let center = bounds.center();
+
+    let loaded: Vec<_> = debug::DynLayer::iter_all_loaded(&layer).collect();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, bounds);
+    match &loaded[0].1.debug(bounds)[..] {
+        [DebugContent::Polygon { points, fill: true }] => assert_eq!(
+            points[..],
+            [
+                center + Point2d::new(0, -10),
+                center + Point2d::new(10, 10),
+                center + Point2d::new(-10, 10),
+            ]
+        ),
+        _ => panic!("expected a single filled polygon"),
+    }
+}
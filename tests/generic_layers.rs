@@ -0,0 +1,1079 @@
+use std::ops::Range;
+
+use generic_layers::{ReducedUniformPoint, Reducible};
+use layer_proc_gen::*;
+use vec2::{Bounds, Line, Point2d};
+
+/// Mostly small things with a few big ones in between.
+fn mixed_radius(pos: Point2d) -> i64 {
+    if (pos.x ^ pos.y) % 8 == 0 { 12 } else { 3 }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Wide(Point2d);
+
+impl From<Point2d> for Wide {
+    fn from(value: Point2d) -> Self {
+        Self(value)
+    }
+}
+
+impl Reducible for Wide {
+    const RADIUS_RANGE: Range<i64> = 1..200;
+
+    fn radius(&self) -> i64 {
+        mixed_radius(self.0)
+    }
+
+    fn position(&self) -> Point2d {
+        self.0
+    }
+}
+
+/// Same as [Wide], but knows the actual largest radius.
+#[derive(Clone, PartialEq, Debug)]
+struct Tight(Point2d);
+
+impl From<Point2d> for Tight {
+    fn from(value: Point2d) -> Self {
+        Self(value)
+    }
+}
+
+impl Reducible for Tight {
+    const RADIUS_RANGE: Range<i64> = 1..200;
+
+    fn radius(&self) -> i64 {
+        mixed_radius(self.0)
+    }
+
+    fn position(&self) -> Point2d {
+        self.0
+    }
+
+    fn conflict_radius(&self) -> i64 {
+        12
+    }
+}
+
+#[test]
+fn per_point_conflict_radius() {
+    let wide = Layer::<ReducedUniformPoint<Wide, 4, 0>>::default();
+    let tight = Layer::<ReducedUniformPoint<Tight, 4, 0>>::default();
+    let bounds = Bounds {
+        min: Point2d::splat(-100),
+        max: Point2d::splat(100),
+    };
+    let wide: Vec<_> = wide
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter().map(|p| p.0))
+        .collect();
+    let tight: Vec<_> = tight
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter().map(|p| p.0))
+        .collect();
+    assert!(wide.len() > 50);
+    assert_eq!(wide, tight);
+}
+
+#[test]
+fn chunk_edges() {
+    type Points = generic_layers::UniformPoint<Wide, 3, 0>;
+    let index = Point2d { x: -2, y: 5 }.map(GridIndex::<Points>::from_raw);
+    let bounds = Points::bounds(index);
+    assert_eq!(Points::pos_to_grid(bounds.min), index);
+    let right = Point2d::new(bounds.max.x, bounds.min.y);
+    assert_eq!(
+        Points::pos_to_grid(right),
+        index + Point2d::new(1, 0).map(GridIndex::from_raw)
+    );
+    let below = Point2d::new(bounds.min.x, bounds.max.y);
+    assert_eq!(
+        Points::pos_to_grid(below),
+        index + Point2d::new(0, 1).map(GridIndex::from_raw)
+    );
+    let last = bounds.max - Point2d::splat(1);
+    assert_eq!(Points::pos_to_grid(last), index);
+
+    // Points generated by a chunk are owned by that chunk.
+    let layer = Layer::<Points>::default();
+    for index in Bounds::point(index)
+        .pad(Point2d::splat(GridIndex::from_raw(20)))
+        .iter()
+    {
+        for p in layer.get(index).points {
+            assert_eq!(Points::pos_to_grid(p.0), index);
+        }
+    }
+}
+
+#[test]
+fn independent_worlds() {
+    type Points = ReducedUniformPoint<Wide, 4, 0>;
+    let world = |seed| Layer::<Points>::new(Layer::new(Seed(seed)));
+    let bounds = Bounds {
+        min: Point2d::splat(-50),
+        max: Point2d::splat(50),
+    };
+    let points = |layer: &Layer<Points>, bounds| -> Vec<_> {
+        layer
+            .get_range(bounds)
+            .flat_map(|c| c.points.into_iter().map(|p| p.0))
+            .collect()
+    };
+
+    let a = world(1);
+    let b = world(2);
+    // Interleave accesses to both worlds.
+    let mut a_points = vec![];
+    let mut b_points = vec![];
+    for index in Points::bounds_to_grid(bounds).iter() {
+        a_points.extend(a.get(index).points.into_iter().map(|p| p.0));
+        b_points.extend(b.get(index).points.into_iter().map(|p| p.0));
+    }
+    assert_ne!(a_points, b_points);
+    assert_eq!(a_points, points(&world(1), bounds));
+    assert_eq!(b_points, points(&world(2), bounds));
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct RightWins(Point2d);
+
+impl From<Point2d> for RightWins {
+    fn from(value: Point2d) -> Self {
+        Self(value)
+    }
+}
+
+impl Reducible for RightWins {
+    const RADIUS_RANGE: Range<i64> = 8..9;
+
+    fn radius(&self) -> i64 {
+        8
+    }
+
+    fn position(&self) -> Point2d {
+        self.0
+    }
+}
+
+/// Same as [RightWins], but prefers the lower x coordinate.
+#[derive(Clone, PartialEq, Debug)]
+struct LeftWins(Point2d);
+
+impl From<Point2d> for LeftWins {
+    fn from(value: Point2d) -> Self {
+        Self(value)
+    }
+}
+
+impl Reducible for LeftWins {
+    const RADIUS_RANGE: Range<i64> = 8..9;
+
+    fn radius(&self) -> i64 {
+        8
+    }
+
+    fn position(&self) -> Point2d {
+        self.0
+    }
+
+    fn wins_over(&self, other: &Self) -> bool {
+        (-self.0.x, self.0.y) > (-other.0.x, other.0.y)
+    }
+}
+
+#[test]
+fn custom_winner() {
+    let bounds = Bounds {
+        min: Point2d::splat(-100),
+        max: Point2d::splat(100),
+    };
+    let right: Vec<_> = Layer::<ReducedUniformPoint<RightWins, 4, 0>>::default()
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter().map(|p| p.0))
+        .collect();
+    let left: Vec<_> = Layer::<ReducedUniformPoint<LeftWins, 4, 0>>::default()
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter().map(|p| p.0))
+        .collect();
+    assert!(!left.is_empty());
+    assert_ne!(left, right);
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Dense(Point2d);
+
+impl From<Point2d> for Dense {
+    fn from(value: Point2d) -> Self {
+        Self(value)
+    }
+}
+
+impl Reducible for Dense {
+    const RADIUS_RANGE: Range<i64> = 1..2;
+    const POINTS: Option<u8> = Some(5);
+
+    fn radius(&self) -> i64 {
+        1
+    }
+
+    fn position(&self) -> Point2d {
+        self.0
+    }
+}
+
+#[test]
+fn fixed_point_count() {
+    let layer = Layer::<ReducedUniformPoint<Dense, 6, 0>>::default();
+    let raw_points: &Layer<generic_layers::UniformPoint<Dense, 6, 0>> = &layer;
+    for index in Bounds::point(point!(0, 0)).pad(point!(3, 3)).iter() {
+        assert_eq!(raw_points.get(index.same_index()).points.len(), 5);
+        assert!(!layer.get(index).points.is_empty());
+    }
+}
+
+#[test]
+fn points_independent_of_bounds_representation() {
+    type Points = generic_layers::UniformPoint<Wide, 5, 0>;
+    let layer = Layer::<Points>::default();
+    for index in Bounds::point(point!(0, 0)).pad(point!(5, 5)).iter() {
+        let half_open = Points::bounds(index);
+        let inclusive = Bounds {
+            min: half_open.min,
+            max: half_open.max - Point2d::splat(1),
+        };
+        let computed = Points::compute_in_bounds(&Seed::default(), index, inclusive);
+        assert_eq!(computed, layer.get(index));
+    }
+}
+
+#[derive(Clone, Default)]
+struct Heavy(Vec<Point2d>);
+
+impl Chunk for Heavy {
+    type LayerStore<T> = std::sync::Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        Heavy(bounds.iter().step_by(100).collect())
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+impl debug::Debug for Heavy {}
+
+impl generic_layers::Summarize for Heavy {
+    type Summary = usize;
+
+    fn summarize(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[test]
+fn summary_survives_eviction() {
+    let summaries = Layer::<generic_layers::Summarized<Heavy>>::new(Layer::new(()));
+    let full: &Layer<Heavy> = &summaries;
+    let index = point!(2, -1);
+    let expected = full.get(index).0.len();
+    assert_eq!(summaries.get_summary(index), expected);
+    summaries.evict_full(index);
+    assert!(!full.grid().contains(index));
+    assert_eq!(summaries.get_summary(index), expected);
+    // Getting the summary did not reload the full chunk.
+    assert!(!full.grid().contains(index));
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Town(Point2d);
+
+impl From<Point2d> for Town {
+    fn from(value: Point2d) -> Self {
+        Self(value)
+    }
+}
+
+impl Reducible for Town {
+    const RADIUS_RANGE: Range<i64> = 20..21;
+
+    fn radius(&self) -> i64 {
+        20
+    }
+
+    fn position(&self) -> Point2d {
+        self.0
+    }
+
+    fn priority(&self) -> i64 {
+        100
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Resource(Point2d);
+
+impl From<Point2d> for Resource {
+    fn from(value: Point2d) -> Self {
+        Self(value)
+    }
+}
+
+impl Reducible for Resource {
+    const RADIUS_RANGE: Range<i64> = 5..6;
+
+    fn radius(&self) -> i64 {
+        5
+    }
+
+    fn position(&self) -> Point2d {
+        self.0
+    }
+
+    fn priority(&self) -> i64 {
+        1
+    }
+}
+
+#[test]
+fn towns_suppress_resources() {
+    type Merged = generic_layers::MergedReducedPoints<Town, Resource, 5, 0, 1>;
+    let merged = Layer::<Merged>::default();
+    let bounds = Bounds {
+        min: Point2d::splat(-300),
+        max: Point2d::splat(300),
+    };
+    // Towns just outside of the bounds can also suppress resources.
+    let all_towns: Vec<_> = merged
+        .first
+        .get_range(bounds.pad(Point2d::splat(25)))
+        .flat_map(|c| c.points.into_iter())
+        .collect();
+    let towns: Vec<_> = merged
+        .first
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter())
+        .collect();
+    let resources: Vec<_> = merged
+        .second
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter())
+        .collect();
+    let (merged_towns, merged_resources): (Vec<_>, Vec<_>) = merged
+        .get_range(bounds)
+        .map(|c| (c.first, c.second))
+        .unzip();
+    let merged_towns: Vec<_> = merged_towns.into_iter().flatten().collect();
+    let merged_resources: Vec<_> = merged_resources.into_iter().flatten().collect();
+
+    // Towns are never removed.
+    assert_eq!(merged_towns, towns);
+    let near_town = |r: &Resource| all_towns.iter().any(|t| t.0.manhattan_dist(r.0) < 25);
+    assert!(
+        resources.iter().any(near_town),
+        "test needs a resource close to a town"
+    );
+    assert!(!merged_resources.iter().any(near_town));
+    let kept: Vec<_> = resources
+        .iter()
+        .filter(|r| !near_town(r))
+        .cloned()
+        .collect();
+    assert_eq!(merged_resources, kept);
+}
+
+#[test]
+fn reseed() {
+    type Points = ReducedUniformPoint<Wide, 4, 0>;
+    let bounds = Bounds {
+        min: Point2d::splat(-100),
+        max: Point2d::splat(100),
+    };
+    let points = |layer: &Layer<Points>| -> Vec<_> {
+        layer
+            .get_range(bounds)
+            .flat_map(|c| c.points.into_iter().map(|p| p.0))
+            .collect()
+    };
+    let mut layer = Layer::<Points>::default();
+    let old = points(&layer);
+
+    let region = Points::covering_grid_bounds(bounds);
+    layer.reseed(Seed(1), &[region]);
+    assert!(layer.is_loaded_in_grid_bounds(region));
+    let new = points(&layer);
+    assert_ne!(old, new);
+
+    // Same result as a world that was created with the new seed.
+    let fresh = Layer::<Points>::new(Layer::new(Seed(1)));
+    assert_eq!(new, points(&fresh));
+}
+
+/// Radius of a bit more than half a chunk, so conflicts reach across more than one chunk.
+#[derive(Clone, PartialEq, Debug)]
+struct Large(Point2d);
+
+impl From<Point2d> for Large {
+    fn from(value: Point2d) -> Self {
+        Self(value)
+    }
+}
+
+impl Reducible for Large {
+    const RADIUS_RANGE: Range<i64> = 9..10;
+
+    fn radius(&self) -> i64 {
+        9
+    }
+
+    fn position(&self) -> Point2d {
+        self.0
+    }
+}
+
+#[test]
+fn radius_just_over_a_chunk() {
+    type Points = ReducedUniformPoint<Large, 4, 0>;
+    // A point at a chunk's `min` looks 9 + 10 = 19 units, i.e. just over one 16 unit chunk, to the left.
+    let pad = Point2d::splat(9 + Large::RADIUS_RANGE.end);
+    let scanned = Points::bounds_to_grid(Bounds::point(Point2d::splat(0)).pad(pad));
+    assert_eq!((scanned.min.x.0, scanned.max.x.0), (-2, 2));
+
+    // Compare with a brute force reduction over all raw points.
+    let reduced = Layer::<Points>::default();
+    let bounds = Bounds {
+        min: Point2d::splat(-200),
+        max: Point2d::splat(200),
+    };
+    let raw_layer: &Layer<generic_layers::UniformPoint<Large, 4, 0>> = &reduced;
+    let raw: Vec<_> = raw_layer
+        .get_range(bounds.pad(pad))
+        .flat_map(|c| c.points.into_iter())
+        .collect();
+    let expected: Vec<_> = raw_layer
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter())
+        .filter(|p| {
+            !raw.iter()
+                .any(|other| other != p && other.0.manhattan_dist(p.0) < 18 && other.wins_over(p))
+        })
+        .collect();
+    let actual: Vec<_> = reduced
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter())
+        .collect();
+    let unreduced: usize = raw_layer.get_range(bounds).map(|c| c.points.len()).sum();
+    assert!(actual.len() < unreduced, "test needs some conflicts");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn world_seed_changes_points() {
+    type Points = generic_layers::UniformPoint<Wide, 4, 0>;
+    let bounds = Bounds {
+        min: Point2d::splat(-100),
+        max: Point2d::splat(100),
+    };
+    let points = |seed| -> Vec<_> {
+        Layer::<Points>::new(Seed(seed))
+            .get_range(bounds)
+            .flat_map(|c| c.points.into_iter().map(|p| p.0))
+            .collect()
+    };
+    assert_eq!(points(1), points(1));
+    assert_ne!(points(1), points(2));
+    assert_ne!(points(0), points(12345));
+}
+
+#[test]
+fn config_matches_const_generics() {
+    use generic_layers::{ConfiguredUniformPoint, LayerConfig, UniformPoint};
+    let bounds = Bounds {
+        min: Point2d::splat(-100),
+        max: Point2d::splat(100),
+    };
+    let configured = |config| -> Vec<_> {
+        Layer::<ConfiguredUniformPoint<Wide, 4>>::new(config)
+            .get_range(bounds)
+            .flat_map(|c| c.points.into_iter().map(|p| p.0))
+            .collect()
+    };
+    let by_const: Vec<_> = Layer::<UniformPoint<Wide, 4, 3>>::new(Seed(5))
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter().map(|p| p.0))
+        .collect();
+    let config = LayerConfig {
+        seed: Seed(5),
+        salt: 3,
+        points: None,
+    };
+    assert_eq!(configured(config), by_const);
+    assert_ne!(configured(LayerConfig { salt: 4, ..config }), by_const);
+
+    // Overriding the number of points.
+    let dense: Vec<_> = Layer::<UniformPoint<Dense, 4, 3>>::new(Seed(5))
+        .get_range(bounds)
+        .flat_map(|c| c.points.into_iter().map(|p| p.0))
+        .collect();
+    let config = LayerConfig {
+        points: Some(5),
+        ..config
+    };
+    assert_eq!(configured(config), dense);
+}
+
+#[test]
+fn world_seed_reaches_reduced_points() {
+    // Large chunks, so points of different seeds are unlikely to coincide by chance.
+    type Points = ReducedUniformPoint<Tight, 8, 0>;
+    let index = GridPoint::new(GridIndex::from_raw(3), GridIndex::from_raw(-2));
+    let points = |layer: &Layer<Points>| -> Vec<_> {
+        layer.get(index).points.into_iter().map(|p| p.0).collect()
+    };
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    for i in 0..10 {
+        let index = index + GridPoint::new(GridIndex::from_raw(i), GridIndex::from_raw(0));
+        let first = Layer::<Points>::new(Layer::new(Seed(1)));
+        let second = Layer::<Points>::new(Layer::new(Seed(2)));
+        a.extend(first.get(index).points.into_iter().map(|p| p.0));
+        b.extend(second.get(index).points.into_iter().map(|p| p.0));
+    }
+    assert!(!a.is_empty() && !b.is_empty());
+    assert!(a.iter().all(|p| !b.contains(p)), "{a:?} {b:?}");
+
+    // Reloading a chunk gives the same points again.
+    let layer = Layer::<Points>::new(Layer::new(Seed(1)));
+    let before = points(&layer);
+    layer.clear(Points::bounds(index));
+    assert!(!layer.grid().contains(index));
+    assert_eq!(points(&layer), before);
+}
+
+#[test]
+fn poisson_min_distance() {
+    type Points = generic_layers::PoissonPoint<6, 0, 10>;
+    let layer = Layer::<Points>::new(Layer::new(Seed(3)));
+    let mut points = vec![];
+    for index in Bounds::point(GridPoint::splat(GridIndex::from_raw(0)))
+        .pad(GridPoint::splat(GridIndex::from_raw(1)))
+        .iter()
+    {
+        let bounds = Points::bounds(index);
+        for &p in layer.get(index).points.iter() {
+            assert!(bounds.x_range().contains(&p.x) && bounds.y_range().contains(&p.y));
+            points.push(p);
+        }
+    }
+    for (i, a) in points.iter().enumerate() {
+        for b in &points[i + 1..] {
+            assert!(a.dist_squared(*b) >= 10 * 10, "{a:?} {b:?}");
+        }
+    }
+    // Densely packed, even at the chunk borders: 192 * 192 / 10^2 ≈ 370.
+    assert!(points.len() > 200, "{}", points.len());
+}
+
+#[test]
+fn value_noise_seams() {
+    type Noise = generic_layers::ValueNoise<4, 0>;
+    let layer = Layer::<Noise>::new(Seed(5));
+    let (mut min, mut max) = (f32::MAX, f32::MIN);
+    for border in (-64..=64).step_by(16) {
+        for other in -40..40 {
+            for (left, right) in [
+                (Point2d::new(border - 1, other), Point2d::new(border, other)),
+                (Point2d::new(other, border - 1), Point2d::new(other, border)),
+            ] {
+                let (a, b) = (layer.sample(left), layer.sample(right));
+                assert!((a - b).abs() < 0.02, "{left:?}: {a}, {right:?}: {b}");
+                min = min.min(a);
+                max = max.max(a);
+            }
+        }
+    }
+    assert!((0.0..=1.0).contains(&min) && (0.0..=1.0).contains(&max));
+    // Not just a constant.
+    assert!(max - min > 0.3, "{min}..{max}");
+}
+
+#[test]
+fn nearest() {
+    type Points = ReducedUniformPoint<Tight, 5, 0>;
+    let layer = Layer::<Points>::new(Layer::new(Seed(2)));
+    let area = Bounds {
+        min: Point2d::splat(-300),
+        max: Point2d::splat(300),
+    };
+    let all: Vec<Point2d> = layer
+        .get_range(area)
+        .flat_map(|chunk| chunk.points)
+        .map(|p| p.0)
+        .collect();
+    let ring = |a: Point2d, b: Point2d| {
+        let (a, b) = (Points::pos_to_grid(a), Points::pos_to_grid(b));
+        (a.x.0 - b.x.0).abs().max((a.y.0 - b.y.0).abs())
+    };
+
+    let mut beyond_first_ring = 0;
+    for pos in (Bounds {
+        min: Point2d::splat(-100),
+        max: Point2d::splat(100),
+    })
+    .iter()
+    .step_by(7)
+    {
+        let expected = all
+            .iter()
+            .copied()
+            .min_by_key(|&p| (p.dist_squared(pos), p))
+            .unwrap();
+        let (found, point) = layer.nearest(pos, 150).unwrap();
+        assert_eq!(found, expected, "{pos:?}");
+        assert_eq!(point.0, found);
+        // Squaring the radius doesn't overflow.
+        assert_eq!(layer.nearest(pos, i64::MAX).unwrap().0, expected);
+
+        // The first ring containing any point is not necessarily the one with the nearest point.
+        let first_ring = all.iter().map(|&p| ring(p, pos)).min().unwrap();
+        if ring(found, pos) > first_ring {
+            beyond_first_ring += 1;
+        }
+
+        let dist = expected.dist_squared(pos);
+        let radius = (dist as f64).sqrt().floor() as i64;
+        let within = layer.nearest(pos, radius);
+        if radius * radius == dist {
+            assert_eq!(within.unwrap().0, expected);
+        } else {
+            assert_eq!(within, None, "{pos:?} {expected:?}");
+        }
+    }
+    assert!(beyond_first_ring > 0);
+}
+
+/// A random number generator with a fixed algorithm (SplitMix64), for reproducible points everywhere.
+#[derive(Clone)]
+struct Fixed(u64);
+
+impl rand::SeedableRng for Fixed {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self(u64::from_le_bytes(seed))
+    }
+}
+
+impl rand::RngCore for Fixed {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+        }
+    }
+}
+
+#[test]
+fn custom_rng() {
+    use generic_layers::UniformPoint;
+    let points = |layer: &Layer<ReducedUniformPoint<Dense, 6, 2, Fixed>>| -> Vec<_> {
+        layer
+            .get(point!(1, -1))
+            .points
+            .into_iter()
+            .map(|p| p.0)
+            .collect()
+    };
+    let layer = Layer::new(Layer::new(Seed(9)));
+    let fixed = points(&layer);
+    // Same on all platforms, in contrast to `SmallRng`.
+    let expected = [(124, -17), (78, -46), (86, -10), (119, -57), (85, -51)];
+    assert_eq!(fixed, expected.map(|(x, y)| Point2d::new(x, y)));
+    assert_eq!(fixed, points(&Layer::new(Layer::new(Seed(9)))));
+
+    // The default random number generator is still used when none is specified.
+    let default: Vec<_> = Layer::<UniformPoint<Dense, 6, 2>>::new(Seed(9))
+        .get(point!(1, -1))
+        .points
+        .into_iter()
+        .map(|p| p.0)
+        .collect();
+    let small_rng: Vec<_> = Layer::<UniformPoint<Dense, 6, 2, rand::rngs::SmallRng>>::new(Seed(9))
+        .get(point!(1, -1))
+        .points
+        .into_iter()
+        .map(|p| p.0)
+        .collect();
+    assert_eq!(default, small_rng);
+    assert_ne!(default, fixed);
+}
+
+/// One point in each quarter of a chunk, at a fixed offset from the quarter's corner,
+/// so that the points are dense enough for all graph edges to be local.
+#[derive(Clone, Default, PartialEq, Debug)]
+struct Jittered(Vec<Point2d>);
+
+impl Chunk for Jittered {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+    const SIZE: Point2d<u8> = Point2d::splat(4);
+
+    fn compute_in_bounds(_: &Self::Dependencies, _index: GridPoint<Self>, bounds: Bounds) -> Self {
+        let min = bounds.min;
+        let points = [(0, 0), (8, 0), (0, 8), (8, 8)]
+            .into_iter()
+            .map(|(x, y)| {
+                let corner = min + Point2d::new(x, y);
+                let jitter = |a: i64, b: i64| (a * 7 + b * 13).rem_euclid(7) + 1;
+                corner + Point2d::new(jitter(corner.x, corner.y), jitter(corner.y, corner.x))
+            })
+            .collect();
+        Self(points)
+    }
+}
+
+impl debug::Debug for Jittered {}
+
+impl generic_layers::PointChunk for Jittered {
+    fn positions(&self) -> impl Iterator<Item = Point2d> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+fn neighborhood_graph_matches_brute_force<M: generic_layers::Metric + Default + 'static>() {
+    let layer = Layer::<generic_layers::NeighborhoodGraph<Jittered, M>>::new(Layer::new(()));
+    let area = Bounds {
+        min: Point2d::splat(-48),
+        max: Point2d::splat(48),
+    };
+    let jittered: &Layer<Jittered> = &layer;
+    let points: Vec<Point2d> = jittered
+        .get_range(area.pad(Point2d::splat(32)))
+        .flat_map(|chunk| chunk.0)
+        .collect();
+    let normalize = |a: Point2d, b: Point2d| if a < b { (a, b) } else { (b, a) };
+    let inside = |(a, b): &(Point2d, Point2d)| area.contains(*a) && area.contains(*b);
+
+    let metric = M::default();
+    let mut expected = vec![];
+    for (i, &a) in points.iter().enumerate() {
+        for &b in &points[i + 1..] {
+            let dist = metric.dist(a, b);
+            let vetoed = points.iter().any(|&c| {
+                c != a && c != b && metric.dist(a, c) <= dist && metric.dist(c, b) <= dist
+            });
+            if !vetoed {
+                expected.push(normalize(a, b));
+            }
+        }
+    }
+    expected.retain(inside);
+    expected.sort();
+
+    let mut edges: Vec<_> = layer
+        .get_range(area)
+        .flat_map(|chunk| chunk.edges.to_vec())
+        .map(|edge| normalize(edge.start, edge.end))
+        .filter(inside)
+        .collect();
+    edges.sort();
+    // Every edge is only emitted by one chunk.
+    let len = edges.len();
+    edges.dedup();
+    assert_eq!(edges.len(), len);
+    assert_eq!(edges, expected);
+    assert!(edges.len() > 100);
+}
+
+#[test]
+fn neighborhood_graph_euclidean() {
+    neighborhood_graph_matches_brute_force::<generic_layers::Euclidean>();
+}
+
+#[test]
+fn neighborhood_graph_manhattan() {
+    neighborhood_graph_matches_brute_force::<generic_layers::Manhattan>();
+}
+
+/// Counts the distance computations of another metric.
+struct Counting<'a, M>(M, &'a std::cell::Cell<usize>);
+
+impl<M: generic_layers::Metric> generic_layers::Metric for Counting<'_, M> {
+    fn dist(&self, a: Point2d, b: Point2d) -> i64 {
+        self.1.set(self.1.get() + 1);
+        self.0.dist(a, b)
+    }
+
+    fn axis_dist(&self, delta: i64) -> i64 {
+        self.0.axis_dist(delta)
+    }
+}
+
+/// The relative neighborhood graph without any caching or shortcuts, returning the edges and the
+/// rejected edges with their veto.
+fn naive_neighborhood_graph(
+    metric: &impl generic_layers::Metric,
+    chunks: &[(Point2d, Vec<Point2d>)],
+    center: Point2d,
+) -> (Vec<Line>, Vec<(Line, Point2d)>) {
+    let points: Vec<Point2d> = chunks.iter().flat_map(|(_, p)| p.iter().copied()).collect();
+    let start: usize = chunks
+        .iter()
+        .take_while(|(index, _)| *index != center)
+        .map(|(_, p)| p.len())
+        .sum();
+    let n = chunks
+        .iter()
+        .find(|(index, _)| *index == center)
+        .unwrap()
+        .1
+        .len();
+    let mut edges = vec![];
+    let mut rejected = vec![];
+    for i in start..start + n {
+        let a = points[i];
+        for &b in &points[i + 1..] {
+            let dist = metric.dist(a, b);
+            let veto = points.iter().copied().find(|&c| {
+                a != c && b != c && dist >= metric.dist(a, c) && dist >= metric.dist(c, b)
+            });
+            match veto {
+                None => edges.push(a.to(b)),
+                Some(veto) => rejected.push((a.to(b), veto)),
+            }
+        }
+    }
+    (edges, rejected)
+}
+
+fn neighborhood_graph_matches_naive<M: generic_layers::Metric + Copy>(metric: M) {
+    use rand::{Rng as _, SeedableRng as _};
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(5);
+    let center = Point2d::new(0, 0);
+    let (mut cached_calls, mut naive_calls) = (0, 0);
+    for _ in 0..200 {
+        let chunks: Vec<(Point2d, Vec<Point2d>)> = Bounds::point(center)
+            .pad(Point2d::splat(1))
+            .iter()
+            .map(|index| {
+                let points = (0..rng.random_range(0..=3))
+                    // Few distinct coordinates, so there are many ties and duplicates.
+                    .map(|_| {
+                        index * 8 + Point2d::new(rng.random_range(0..8), rng.random_range(0..8))
+                    })
+                    .collect();
+                (index, points)
+            })
+            .collect();
+        let calls = std::cell::Cell::new(0);
+        let mut rejected = vec![];
+        let edges = generic_layers::relative_neighborhood_graph(
+            Counting(metric, &calls),
+            chunks
+                .iter()
+                .map(|(index, points)| (*index, points.as_slice())),
+            center,
+            |&p| p,
+            |&a, &b| a.to(b),
+            |&a, &b, &veto| rejected.push((a.to(b), veto)),
+        );
+        cached_calls += calls.take();
+        let expected = naive_neighborhood_graph(&Counting(metric, &calls), &chunks, center);
+        naive_calls += calls.take();
+        assert_eq!((edges, rejected), expected, "{chunks:?}");
+    }
+    assert!(
+        cached_calls * 3 < naive_calls,
+        "{cached_calls} distance computations, naively {naive_calls}"
+    );
+}
+
+#[test]
+fn neighborhood_graph_matches_naive_euclidean() {
+    neighborhood_graph_matches_naive(generic_layers::Euclidean);
+}
+
+#[test]
+fn neighborhood_graph_matches_naive_manhattan() {
+    neighborhood_graph_matches_naive(generic_layers::Manhattan);
+}
+
+/// Dense west of `x = 0`, sparse east of it.
+#[derive(Clone, Default, PartialEq, Debug)]
+struct Step;
+
+impl Chunk for Step {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+    const SIZE: Point2d<u8> = Point2d::splat(6);
+
+    fn compute_in_bounds(_: &Self::Dependencies, _: GridPoint<Self>, _bounds: Bounds) -> Self {
+        Step
+    }
+}
+
+impl debug::Debug for Step {}
+
+impl generic_layers::ScalarField for Step {
+    fn value(&self, pos: Point2d) -> f32 {
+        if pos.x < 0 { 4.0 } else { 0.5 }
+    }
+}
+
+#[test]
+fn weighted_points_follow_density() {
+    type Weighted = generic_layers::WeightedUniformPoint<Step, 4, 3>;
+    let new = || {
+        Layer::<Weighted>::new(generic_layers::WeightedPointsDeps {
+            seed: Seed(9),
+            density: Layer::new(()),
+        })
+    };
+    let side = |min_x: i64| Bounds {
+        min: Point2d::new(min_x, -160),
+        max: Point2d::new(min_x + 319, 159),
+    };
+    let points = |layer: &Layer<Weighted>, bounds: Bounds| -> Vec<Point2d> {
+        layer
+            .get_grid_range(Weighted::bounds_to_grid(bounds))
+            .flat_map(|chunk| chunk.points.to_vec())
+            .collect()
+    };
+    let layer = new();
+    let dense = points(&layer, side(-320));
+    let sparse = points(&layer, side(0));
+    assert!(dense.iter().all(|p| p.x < 0) && sparse.iter().all(|p| p.x >= 0));
+    // 400 chunks on each side.
+    assert!((1500..=1700).contains(&dense.len()), "{}", dense.len());
+    assert!((150..=250).contains(&sparse.len()), "{}", sparse.len());
+
+    // The same points after reloading.
+    let reloaded = new();
+    assert_eq!(points(&reloaded, side(0)), sparse);
+    assert_eq!(points(&reloaded, side(-320)), dense);
+    layer.clear(side(-320));
+    assert_eq!(points(&layer, side(-320)), dense);
+}
+
+#[test]
+fn reduced_points_snapshot() {
+    // Mixed radii, so the reduction has to decide between overlapping points.
+    let layer = Layer::<ReducedUniformPoint<Wide, 5, 1>>::new(Layer::new(Seed(3)));
+    let bounds = Bounds {
+        min: Point2d::splat(-64),
+        max: Point2d::splat(63),
+    };
+    golden::assert_golden(
+        &golden::snapshot(&layer, bounds),
+        "tests/golden/reduced_points.txt",
+    );
+}
+
+/// A value every 4 units, only depending on the position.
+#[derive(Clone, Default, PartialEq, Debug)]
+struct Samples(Vec<(Point2d, i64)>);
+
+fn samples(bounds: Bounds, step: i64) -> Vec<(Point2d, i64)> {
+    let value = |p: Point2d| (p.x * 31 + p.y * 17).rem_euclid(1000);
+    Bounds {
+        min: bounds.min,
+        max: bounds.max - Point2d::splat(1),
+    }
+    .iter()
+    .filter(|p| p.x % step == 0 && p.y % step == 0)
+    .map(|p| (p, value(p)))
+    .collect()
+}
+
+impl Chunk for Samples {
+    type LayerStore<T> = std::sync::Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+    const SIZE: Point2d<u8> = Point2d::splat(4);
+
+    fn compute_in_bounds(_: &(), _: GridPoint<Self>, bounds: Bounds) -> Self {
+        Self(samples(bounds, 4))
+    }
+}
+
+impl debug::Debug for Samples {}
+
+impl generic_layers::LodChunk for Samples {
+    type Coarse = Vec<(Point2d, i64)>;
+    const MAX_LEVEL: u8 = 3;
+
+    fn compute_coarse(_fine: &Layer<Self>, bounds: Bounds, level: u8) -> Self::Coarse {
+        // Skips the samples in between instead of computing the full chunks.
+        samples(bounds, 4 << level)
+    }
+}
+
+#[test]
+fn lod_seams() {
+    use generic_layers::{Coarse, LodChunk as _};
+    let fine = Layer::<Samples>::new(());
+    let coarse = Layer::<Coarse<Samples, 2>>::new(fine.clone());
+    let fine_value = |pos: Point2d| {
+        let chunk = fine.get(Samples::pos_to_grid(pos));
+        chunk.0.iter().find(|(p, _)| *p == pos).unwrap().1
+    };
+    for index in [point!(0, 0), point!(1, 0), point!(-1, 2)] {
+        let bounds = Coarse::<Samples, 2>::bounds(index);
+        let covered: Vec<GridPoint<Samples>> = index.covering_indices().collect();
+        assert_eq!(covered.len(), 16);
+        let union = covered
+            .iter()
+            .map(|&i| Samples::bounds(i))
+            .reduce(|a, b| a.union(&b))
+            .unwrap();
+        assert_eq!(union, bounds);
+
+        let chunk = coarse.get(index);
+        assert_eq!(chunk.0.len(), 16);
+        for &(pos, value) in &chunk.0 {
+            assert!(bounds.contains(pos));
+            assert_eq!(value, fine_value(pos), "{pos:?}");
+        }
+    }
+    // The coarse chunk starting at x = 64 and the fine chunks left of it meet without
+    // overlapping, and the first coarse column agrees with the fine chunks right of the border.
+    let border: Vec<_> = coarse
+        .get(point!(1, 0))
+        .0
+        .into_iter()
+        .filter(|(p, _)| p.x == 64)
+        .collect();
+    assert_eq!(border.len(), 4);
+    for (pos, value) in border {
+        assert_eq!(Samples::pos_to_grid(pos).x.0, 4);
+        assert_eq!(value, fine_value(pos));
+    }
+
+    let view = |chunks: i64| Bounds {
+        min: Point2d::splat(0),
+        max: Point2d::new(chunks * 16, 16),
+    };
+    assert_eq!(Samples::lod_for(view(8)), 0);
+    assert_eq!(Samples::lod_for(view(9)), 1);
+    assert_eq!(Samples::lod_for(view(16)), 1);
+    assert_eq!(Samples::lod_for(view(17)), 2);
+    assert_eq!(Samples::lod_for(view(1 << 40)), 3);
+}
@@ -0,0 +1,687 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use layer_proc_gen::*;
+use vec2::{Bounds, Point2d};
+
+static SPARSE_COMPUTES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Default)]
+struct SparseChunk(Point2d);
+
+impl Chunk for SparseChunk {
+    type LayerStore<T> = Arc<T>;
+    type Grid = SparseGrid<Self>;
+    type Dependencies = ();
+
+    // A rolling grid of this size would have to evict chunks all the time.
+    const GRID_SIZE: Point2d<u8> = Point2d::splat(0);
+    const GRID_OVERLAP: u8 = 1;
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        SPARSE_COMPUTES.fetch_add(1, Ordering::Relaxed);
+        SparseChunk(bounds.min)
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+fn sparse_far_apart_access() {
+    let layer = Layer::<SparseChunk>::new(());
+    let indices = [
+        Point2d::new(0, 0),
+        Point2d::new(-1_000_000, 7),
+        Point2d::new(1 << 40, -(1 << 40)),
+        Point2d::new(3, 1_000_000_000),
+    ]
+    .map(|p| p.map(GridIndex::<SparseChunk>::from_raw));
+    for index in indices {
+        assert_eq!(layer.get(index).0, SparseChunk::bounds(index).min);
+    }
+    assert_eq!(SPARSE_COMPUTES.load(Ordering::Relaxed), indices.len());
+    // Nothing got evicted, so accessing them again is free.
+    for index in indices {
+        layer.get(index);
+    }
+    assert_eq!(SPARSE_COMPUTES.load(Ordering::Relaxed), indices.len());
+}
+
+#[derive(Clone, Default)]
+struct DenseChunk;
+
+impl Chunk for DenseChunk {
+    type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        DenseChunk
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+fn freshness() {
+    let layer = Layer::<DenseChunk>::new(());
+    let index = Point2d::new(3, 4).map(GridIndex::from_raw);
+    assert_eq!(layer.get_with_freshness(index).1, Freshness::Created);
+    assert_eq!(layer.get_with_freshness(index).1, Freshness::Cached);
+    let other = Point2d::new(-3, 4).map(GridIndex::from_raw);
+    assert_eq!(layer.get_with_freshness(other).1, Freshness::Created);
+    assert_eq!(layer.get_with_freshness(index).1, Freshness::Cached);
+}
+
+#[test]
+fn cached_bounds() {
+    let layer = Layer::<DenseChunk>::new(());
+    let index = Point2d::new(-3, 7).map(GridIndex::from_raw);
+    assert_eq!(layer.grid().bounds_of(index), None);
+    layer.get(index);
+    assert_eq!(
+        layer.grid().bounds_of(index),
+        Some(DenseChunk::bounds(index))
+    );
+    layer.clear(DenseChunk::bounds(index));
+    assert_eq!(layer.grid().bounds_of(index), None);
+}
+
+#[test]
+fn warm() {
+    let layer = Layer::<DenseChunk>::new(());
+    let regions = [
+        Bounds {
+            min: Point2d::new(-2, -1),
+            max: Point2d::new(2, 2),
+        },
+        Bounds::point(Point2d::new(5, 5)),
+    ]
+    .map(|bounds| GridBounds {
+        min: bounds.min.map(GridIndex::from_raw),
+        max: bounds.max.map(GridIndex::from_raw),
+    });
+    assert!(!layer.is_loaded_in_grid_bounds(regions[0]));
+    let mut reports = vec![];
+    layer.warm(&regions, |done, total| reports.push((done, total)));
+    assert_eq!(reports, (1..=13).map(|done| (done, 13)).collect::<Vec<_>>());
+    for region in regions {
+        assert!(layer.is_loaded_in_grid_bounds(region));
+    }
+    assert!(layer.is_loaded_in_bounds(Bounds::point(DenseChunk::bounds(regions[1].min).min)));
+}
+
+#[test]
+fn vision_range_at_zoom_levels() {
+    #[derive(Clone, Default)]
+    struct Roads;
+
+    impl Chunk for Roads {
+        type LayerStore<T> = T;
+        type Grid = RollingGrid<Self>;
+        type Dependencies = ();
+        const SIZE: Point2d<u8> = Point2d::splat(6);
+
+        fn compute_in_bounds(
+            _layer: &Self::Dependencies,
+            _index: GridPoint<Self>,
+            _bounds: Bounds,
+        ) -> Self {
+            Roads
+        }
+
+        fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+    }
+
+    // A camera at a sub-pixel position looking at 200x100 world units.
+    let view = VisionRange::new(Point2d::new(10.5, -3.25), Point2d::new(100., 50.));
+    let grid = |zoom| {
+        let bounds = view.with_zoom(zoom).grid_bounds::<Roads>();
+        (bounds.min.map(|i| i.0), bounds.max.map(|i| i.0))
+    };
+    assert_eq!(grid(1.), (Point2d::new(-3, -2), Point2d::new(3, 2)));
+    assert_eq!(grid(2.), (Point2d::new(-2, -2), Point2d::new(2, 2)));
+    assert_eq!(grid(0.5), (Point2d::new(-4, -3), Point2d::new(5, 3)));
+
+    assert_eq!(
+        view.bounds(),
+        Bounds {
+            min: Point2d::new(-90, -54),
+            max: Point2d::new(111, 47),
+        }
+    );
+    assert_eq!(
+        view.with_padding(Point2d::splat(3)).bounds_for::<Roads>(),
+        Bounds {
+            min: Point2d::new(-157, -121),
+            max: Point2d::new(178, 114),
+        }
+    );
+}
+
+thread_local! {
+    static LOAD_ORDER: std::cell::RefCell<Vec<Point2d>> = const { std::cell::RefCell::new(vec![]) };
+}
+
+#[derive(Clone, Default)]
+struct Recorded;
+
+impl Chunk for Recorded {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        LOAD_ORDER.with_borrow_mut(|order| order.push(index.map(|i| i.0)));
+        Recorded
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+fn load_order_from_center() {
+    let bounds = Bounds {
+        min: Point2d::splat(0),
+        max: Point2d::new(4 * 256 + 10, 10),
+    };
+    let load = |f: &dyn Fn(&Layer<Recorded>)| {
+        LOAD_ORDER.with_borrow_mut(Vec::clear);
+        f(&Layer::new(()));
+        LOAD_ORDER.take()
+    };
+    let centered = load(&|layer| layer.ensure_loaded_in_bounds(bounds));
+    let from_left = load(&|layer| layer.ensure_loaded_in_bounds_from(bounds, Point2d::splat(0)));
+    let from_right = load(&|layer| layer.ensure_loaded_in_bounds_from(bounds, bounds.max));
+    let row = |x: &[i64]| x.iter().map(|&x| Point2d::new(x, 0)).collect::<Vec<_>>();
+    assert_eq!(centered[0], Point2d::new(2, 0));
+    assert_eq!(from_left, row(&[0, 1, 2, 3, 4]));
+    assert_eq!(from_right, row(&[4, 3, 2, 1, 0]));
+
+    // Many ties, which are loaded in the order of `Bounds::iter`, also when reusing the buffer.
+    let square = Bounds::point(Point2d::splat(0)).pad(Point2d::splat(600));
+    let center = Point2d::new(128, 128);
+    let mut expected: Vec<_> = Recorded::bounds_to_grid(square).iter().collect();
+    expected.sort_by_cached_key(|&index| Recorded::bounds(index).center().dist_squared(center));
+    let expected: Vec<_> = expected.iter().map(|index| index.map(|i| i.0)).collect();
+    for _ in 0..3 {
+        let order = load(&|layer| layer.ensure_loaded_in_bounds_from(square, center));
+        assert_eq!(order, expected);
+    }
+}
+
+#[derive(Clone, Default)]
+struct SideScroller;
+
+impl Chunk for SideScroller {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+    const GRID_SIZE: Point2d<u8> = Point2d::new(6, 1);
+    const GRID_OVERLAP: u8 = 1;
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        SideScroller
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+fn wide_but_short_grid() {
+    let layer = Layer::<SideScroller>::new(());
+    // 64 chunks wide and 2 high fit at the same time.
+    let region = GridBounds::<SideScroller> {
+        min: point!(-1000, 0),
+        max: point!(-937, 1),
+    };
+    layer.warm(&[region], |_, _| {});
+    assert!(layer.is_loaded_in_grid_bounds(region));
+    // Moving up evicts the lower row.
+    layer.get(point!(-1000, 2));
+    assert!(!layer.is_loaded_in_grid_bounds(region));
+}
+
+#[test]
+fn plan_load() {
+    let layer = Layer::<Recorded>::new(());
+    let bounds = Bounds {
+        min: Point2d::splat(0),
+        max: Point2d::splat(1000),
+    };
+    layer.ensure_loaded_in_bounds(Bounds::point(Point2d::splat(300)).pad(Point2d::splat(200)));
+    let plan = layer.plan_load(bounds);
+    assert_eq!(plan, layer.plan_load(bounds));
+    assert_eq!(plan.cached.len(), 4);
+    assert_eq!(plan.to_compute.len() + plan.cached.len(), 16);
+
+    LOAD_ORDER.with_borrow_mut(Vec::clear);
+    layer.ensure_loaded_in_bounds(bounds);
+    let mut computed = LOAD_ORDER.take();
+    computed.sort();
+    let mut planned: Vec<_> = plan.to_compute.iter().map(|p| p.map(|i| i.0)).collect();
+    planned.sort();
+    assert_eq!(computed, planned);
+    assert!(layer.plan_load(bounds).to_compute.is_empty());
+}
+
+#[test]
+fn debug_color() {
+    let index = point!(DenseChunk; -5, 12);
+    let color = index.debug_color();
+    assert_eq!(color, point!(DenseChunk; -5, 12).debug_color());
+    for neighbor in DenseChunk::moore_neighborhood(index).into_iter().flatten() {
+        if neighbor != index {
+            assert_ne!(neighbor.debug_color(), color);
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct HalfChunk;
+
+impl Chunk for HalfChunk {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+    const SIZE: Point2d<u8> = Point2d::splat(7);
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        HalfChunk
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+fn same_index_is_identity() {
+    let index = point!(DenseChunk; -3, 5);
+    let same: GridPoint<SparseChunk> = index.same_index();
+    assert_eq!(same.map(|i| i.0), Point2d::new(-3, 5));
+    assert_eq!(SparseChunk::bounds(same), DenseChunk::bounds(index));
+    let covering: Vec<GridPoint<SparseChunk>> = index.covering_indices().collect();
+    assert_eq!(covering, [same]);
+}
+
+#[test]
+fn covering_indices_of_different_sizes() {
+    let index = point!(DenseChunk; -3, 5);
+    let halves: Vec<_> = index
+        .covering_indices::<HalfChunk>()
+        .map(|i| i.map(|i| i.0))
+        .collect();
+    assert_eq!(
+        halves,
+        [
+            Point2d::new(-6, 10),
+            Point2d::new(-5, 10),
+            Point2d::new(-6, 11),
+            Point2d::new(-5, 11)
+        ]
+    );
+    for half in index.covering_indices::<HalfChunk>() {
+        let wholes: Vec<GridPoint<DenseChunk>> = half.covering_indices().collect();
+        assert_eq!(wholes, [index]);
+    }
+}
+
+#[test]
+fn update_loaded_bounds() {
+    for size in [3, 10, 30] {
+        let layer = Layer::<Recorded>::new(());
+        let old = Bounds {
+            min: point!(0, 0),
+            max: point!(size + 1, 5),
+        };
+        layer.warm(&[old], |_, _| {});
+        LOAD_ORDER.with_borrow_mut(Vec::clear);
+        let new = Bounds {
+            min: point!(1, 0),
+            max: point!(size + 2, 5),
+        };
+        layer.update_loaded_bounds(old, new);
+        let loaded = LOAD_ORDER.take();
+        assert_eq!(
+            loaded,
+            (0..=4)
+                .map(|y| Point2d::new(size + 1, y))
+                .collect::<Vec<_>>()
+        );
+        assert!(layer.is_loaded_in_grid_bounds(new));
+        for y in 0..=4 {
+            assert!(!layer.grid().contains(point!(0, y)));
+        }
+    }
+}
+
+#[test]
+fn stats() {
+    let layer = Layer::<DenseChunk>::new(());
+    let stats = |computes, cache_hits, evictions, occupied_slots| GridStats {
+        computes,
+        cache_hits,
+        evictions,
+        occupied_slots,
+    };
+    assert_eq!(layer.grid().stats(), GridStats::default());
+    layer.get(point!(0, 0));
+    layer.get(point!(0, 0));
+    assert_eq!(layer.grid().stats(), stats(1, 1, 0, 1));
+
+    // All of these share a grid cell, so the fourth one evicts the first.
+    for x in 1..4 {
+        layer.get(point!(x * 32, 0));
+    }
+    assert_eq!(layer.grid().stats(), stats(4, 1, 1, 3));
+
+    layer.grid().reset_stats();
+    assert_eq!(layer.grid().stats(), stats(0, 0, 0, 3));
+    layer.get(point!(5, 5));
+    assert_eq!(layer.grid().stats(), stats(1, 0, 0, 4));
+}
+
+#[test]
+fn runtime_size() {
+    // Chunks 32 apart share a cell of the default 32x32 grid, which holds 3 chunks per cell.
+    let load = |layer: &Layer<DenseChunk>| {
+        for x in 0..6 {
+            layer.get(point!(x * 32, 0));
+        }
+        layer.grid().stats().evictions
+    };
+    assert_eq!(load(&Layer::new(())), 3);
+
+    let wider = Layer::<DenseChunk>::with_grid(RollingGrid::with_size(Point2d::new(6, 5), 3), ());
+    assert_eq!(load(&wider), 0);
+    assert_eq!(wider.grid().stats().occupied_slots, 6);
+
+    let deeper = Layer::<DenseChunk>::with_grid(RollingGrid::with_size(Point2d::splat(5), 6), ());
+    assert_eq!(load(&deeper), 0);
+    assert_eq!(deeper.grid().stats().occupied_slots, 6);
+}
+
+#[test]
+#[should_panic = "overlap must not be zero"]
+fn runtime_size_without_overlap() {
+    RollingGrid::<DenseChunk>::with_size(Point2d::splat(5), 0);
+}
+
+#[test]
+fn with_range() {
+    let layer = Layer::<SparseChunk>::new(());
+    let bounds = Bounds {
+        min: Point2d::new(-300, 10),
+        max: Point2d::new(300, 600),
+    };
+    let mut seen = vec![];
+    layer.with_range(bounds, |index, chunk| {
+        assert_eq!(chunk.0, SparseChunk::bounds(index).min);
+        seen.push(index);
+    });
+    let expected: Vec<_> = SparseChunk::bounds_to_grid(bounds).iter().collect();
+    assert_eq!(seen.len(), 4 * 3);
+    assert_eq!(seen, expected);
+}
+
+#[test]
+fn scan() {
+    let layer = Layer::<Recorded>::new(());
+    let bounds = Bounds {
+        min: Point2d::new(-300, -10),
+        max: Point2d::new(300, 600),
+    };
+    LOAD_ORDER.with_borrow_mut(Vec::clear);
+    let mut scan = layer.scan(bounds);
+    // Nothing is generated before it is needed.
+    assert!(LOAD_ORDER.with_borrow(Vec::is_empty));
+    let (first, Recorded) = scan.next().unwrap();
+    assert_eq!(LOAD_ORDER.with_borrow(Vec::len), 1);
+    let indices: Vec<_> = std::iter::once(first)
+        .chain(scan.map(|(index, Recorded)| index))
+        .map(|index| index.map(|i| i.0))
+        .collect();
+    let expected: Vec<_> = (-1..=2)
+        .flat_map(|y| (-2..=1).map(move |x| Point2d::new(x, y)))
+        .collect();
+    assert_eq!(indices, expected);
+    let grid: Vec<_> = Recorded::bounds_to_grid(bounds)
+        .iter()
+        .map(|index| index.map(|i| i.0))
+        .collect();
+    assert_eq!(indices, grid);
+    assert_eq!(LOAD_ORDER.take(), expected);
+}
+
+#[test]
+fn grid_bounds_array() {
+    let bounds: GridBounds<DenseChunk> = Bounds {
+        min: point!(-5, -300),
+        max: point!(2, -1),
+    };
+    assert_eq!(bounds.to_array(), [-5, -300, 2, -1]);
+    assert_eq!(GridBounds::from_array(bounds.to_array()), bounds);
+    let single: GridBounds<DenseChunk> = Bounds::point(point!(0, 0));
+    assert_eq!(single.to_array(), [0, 0, 1, 1]);
+    assert_eq!(GridBounds::from_array([0, 0, 1, 1]), single);
+    let empty = GridBounds::<DenseChunk>::from_array([3, 3, 2, 2]);
+    assert_eq!(empty.iter().count(), 0);
+    assert_eq!(empty.to_array(), [3, 3, 2, 2]);
+}
+
+#[test]
+fn nearest_resident() {
+    let dense = Layer::<DenseChunk>::new(());
+    assert!(dense.grid().nearest_resident(Point2d::splat(0)).is_none());
+    dense.warm(
+        &[Bounds {
+            min: point!(-1, -1),
+            max: point!(2, 2),
+        }],
+        |_, _| {},
+    );
+    // Inside a cached chunk.
+    let (index, DenseChunk) = dense.grid().nearest_resident(Point2d::new(10, 20)).unwrap();
+    assert_eq!(index, point!(0, 0));
+
+    // Punch a hole into the middle.
+    dense.grid().clear(point!(0, 0), &dense);
+    assert!(!dense.grid().contains(point!(0, 0)));
+    let nearest = |pos| dense.grid().nearest_resident(pos).unwrap().0;
+    assert_eq!(nearest(Point2d::new(250, 100)), point!(1, 0));
+    assert_eq!(nearest(Point2d::new(100, 3)), point!(0, -1));
+    assert_eq!(nearest(Point2d::new(2, 5)), point!(-1, 0));
+    // Equally close to the left and the top neighbor.
+    assert_eq!(nearest(Point2d::new(2, 2)), point!(-1, 0));
+    assert_eq!(nearest(Point2d::new(5000, 300)), point!(1, 1));
+}
+
+#[test]
+fn rolling_grid_memory_is_bounded() {
+    let layer = Layer::<DenseChunk>::new(());
+    // Default `GRID_SIZE` of 5 and `GRID_OVERLAP` of 3.
+    let capacity = 32 * 32 * 3;
+    // Drive far away, loading everything around the "player" on the way.
+    for step in 0..200 {
+        let pos = Point2d::new(step * 1000, step * -300);
+        layer.ensure_loaded_in_bounds(Bounds::point(pos).pad(Point2d::splat(2000)));
+        assert!(layer.grid().iter_all_loaded().count() <= capacity);
+    }
+    // The chunks behind the player got evicted to make room.
+    assert!(!layer.grid().contains(point!(0, 0)));
+    assert!(layer.grid().stats().evictions > 0);
+}
+
+thread_local! {
+    static DROPPED: std::cell::RefCell<Vec<Point2d>> = const { std::cell::RefCell::new(vec![]) };
+}
+
+#[derive(Clone, Default)]
+struct Tracked(Option<Point2d>);
+
+impl Chunk for Tracked {
+    type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        Tracked(Some(index.map(|i| i.0)))
+    }
+
+    fn on_drop(&self, _layer: &Self::Dependencies, index: GridPoint<Self>) {
+        assert_eq!(
+            self.0,
+            Some(index.map(|i| i.0)),
+            "dropped a chunk that was never cached"
+        );
+        DROPPED.with_borrow_mut(|dropped| dropped.push(index.map(|i| i.0)));
+    }
+}
+
+#[test]
+fn on_drop_once() {
+    let layer = Layer::<Tracked>::new(());
+    let chunk = layer.get(point!(0, 0));
+    // All of these share a grid cell, so the fourth one evicts the first.
+    for x in 1..4 {
+        layer.get(point!(x * 32, 0));
+    }
+    assert_eq!(DROPPED.take(), [Point2d::new(0, 0)]);
+    // Handed out copies outlive the cached chunk.
+    assert_eq!(chunk.0, Some(Point2d::new(0, 0)));
+
+    layer.clear(Tracked::bounds(point!(32, 0)));
+    assert_eq!(DROPPED.take(), [Point2d::new(32, 0)]);
+    layer
+        .grid()
+        .set(&(), point!(5, 5), Tracked(Some(Point2d::new(5, 5))));
+    assert_eq!(DROPPED.take(), []);
+
+    drop(layer);
+    let mut dropped = DROPPED.take();
+    dropped.sort();
+    assert_eq!(
+        dropped,
+        [Point2d::new(5, 5), Point2d::new(64, 0), Point2d::new(96, 0)]
+    );
+}
+
+#[test]
+fn double_clear_drops_once() {
+    let layer = Layer::<Tracked>::new(());
+    layer.get(point!(7, 7));
+    layer.clear(Tracked::bounds(point!(7, 7)));
+    layer.clear(Tracked::bounds(point!(7, 7)));
+    assert_eq!(DROPPED.take(), [Point2d::new(7, 7)]);
+    drop(layer);
+    assert_eq!(DROPPED.take(), []);
+}
+
+#[test]
+fn index_count() {
+    let single = Bounds::point(point!(DenseChunk; 3, -4));
+    assert!(!single.is_empty());
+    assert_eq!(single.index_count(), 1);
+    assert_eq!(single.iter().count(), 1);
+
+    // A single world position touches the chunk it is in.
+    let pos = Point2d::new(100, -7);
+    let touched = DenseChunk::bounds_to_grid(Bounds::point(pos));
+    assert_eq!(touched, Bounds::point(DenseChunk::pos_to_grid(pos)));
+    assert_eq!(touched.index_count(), 1);
+    // A zero-area world area touches no chunk, but is still covered by the chunk it is in.
+    let zero_area = Bounds { min: pos, max: pos };
+    assert_eq!(DenseChunk::bounds_to_grid(zero_area).index_count(), 0);
+    assert_eq!(DenseChunk::covering_grid_bounds(zero_area), touched);
+
+    let wide = GridBounds::<DenseChunk> {
+        min: point!(-2, 5),
+        max: point!(5, 7),
+    };
+    assert_eq!(wide.index_count(), 14);
+    assert_eq!(wide.iter().count(), wide.index_count());
+
+    let far = GridBounds::<DenseChunk> {
+        min: point!(i64::MIN, 0),
+        max: point!(i64::MAX, 1),
+    };
+    assert_eq!(far.index_count(), usize::MAX);
+}
+
+#[test]
+fn inverted_bounds_are_empty() {
+    for inverted in [
+        GridBounds::<DenseChunk> {
+            min: point!(3, 0),
+            max: point!(2, 5),
+        },
+        GridBounds {
+            min: point!(0, 3),
+            max: point!(5, 2),
+        },
+        GridBounds {
+            min: point!(1, 1),
+            max: point!(-1, -1),
+        },
+    ] {
+        assert!(inverted.is_empty());
+        assert_eq!(inverted.index_count(), 0);
+        assert_eq!(inverted.iter().count(), 0);
+        assert_eq!(inverted.iter_spiral(inverted.center()).count(), 0);
+        assert_eq!(inverted.iter_border().count(), 0);
+    }
+
+    let layer = Layer::<DenseChunk>::new(());
+    layer.ensure_loaded_in_bounds(Bounds {
+        min: Point2d::new(100, 0),
+        max: Point2d::new(-100, 50),
+    });
+    assert_eq!(layer.grid().stats(), GridStats::default());
+    assert_eq!(layer.grid().stats().occupied_slots, 0);
+}
+
+#[test]
+fn loaded() {
+    let layer = Layer::<DenseChunk>::new(());
+    assert_eq!(layer.loaded().count(), 0);
+    // The first two share a grid cell.
+    let indices = [point!(1, 2), point!(33, 2), point!(-7, 40)];
+    for index in indices {
+        layer.get(index);
+    }
+    // Loading again doesn't add another entry.
+    layer.get(indices[0]);
+    let mut loaded: Vec<_> = layer.loaded().map(|(index, _)| index).collect();
+    loaded.sort_by_key(|index| index.map(|i| i.0));
+    let mut expected = indices.to_vec();
+    expected.sort_by_key(|index| index.map(|i| i.0));
+    assert_eq!(loaded, expected);
+
+    layer.clear(DenseChunk::bounds(indices[1]));
+    assert_eq!(layer.loaded().count(), 2);
+}
@@ -0,0 +1,76 @@
+use layer_proc_gen::*;
+use vec2::{Bounds, Point2d};
+
+#[path = "../examples/common/mod.rs"]
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+#[test]
+fn roads_read_their_neighbors() {
+    let locations = Layer::<ReducedLocations>::default();
+    let roads = Layer::<Roads>::new(RoadsDeps {
+        intersections: locations.clone(),
+    });
+    let index = point!(3, -2);
+    roads.get(index);
+    let deps = roads.inspect_deps(index);
+    let neighbors: Vec<_> = ReducedLocations::moore_neighborhood(index.same_index())
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(deps.len(), neighbors.len());
+    for (dep, neighbor) in deps.iter().zip(neighbors) {
+        assert_eq!(dep.index, neighbor.map(|i| i.0));
+        assert_eq!(dep.bounds, ReducedLocations::bounds(neighbor));
+        assert_eq!(
+            dep.downcast::<ReducedLocations>(),
+            Some(&locations.get(neighbor))
+        );
+    }
+
+    // Inspecting a chunk whose dependencies are not loaded does not load them.
+    let far = point!(1000, 1000);
+    let deps = roads.inspect_deps(far);
+    assert_eq!(deps.len(), 9);
+    assert!(deps.iter().all(|dep| dep.chunk.is_none()));
+    let far_bounds = Roads::vision_range(Roads::bounds(far));
+    assert!(!locations.is_loaded_in_bounds(Bounds::point(far_bounds.min + Point2d::splat(100))));
+    assert!(!roads.grid().contains(far));
+}
+
+/// Reads the [ReducedLocations] chunk with the same index via [Layer::get_shallow].
+#[derive(Clone, Default)]
+struct Shallow;
+
+impl Chunk for Shallow {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Layer<ReducedLocations>;
+
+    const SIZE: Point2d<u8> = ReducedLocations::SIZE;
+
+    fn compute_in_bounds(
+        locations: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        locations.get_shallow(index.same_index());
+        Shallow
+    }
+
+    fn clear(locations: &Self::Dependencies, index: GridPoint<Self>) {
+        locations.clear(Self::bounds(index));
+    }
+}
+
+#[test]
+fn inspecting_shallow_reads_does_not_cache() {
+    let locations = Layer::<ReducedLocations>::default();
+    let shallow = Layer::<Shallow>::new(locations.clone());
+    let index = point!(5, 5);
+    let deps = shallow.inspect_deps(index);
+    assert_eq!(deps.len(), 1);
+    assert!(deps[0].chunk.is_none());
+    assert!(!locations.grid().contains(index.same_index()));
+}
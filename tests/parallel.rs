@@ -0,0 +1,38 @@
+use std::num::NonZeroUsize;
+
+use layer_proc_gen::*;
+use vec2::{Bounds, Point2d};
+
+#[path = "../examples/common/mod.rs"]
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+#[test]
+fn parallel_matches_serial() {
+    // As large as fits into the cache of `Roads` at once.
+    let region = Bounds::point(Point2d::new(-5000, 3000)).pad(Point2d::splat(950));
+    let grid = Roads::bounds_to_grid(region);
+    assert_eq!(grid.iter().count(), 30 * 30);
+    let deps = || RoadsDeps {
+        intersections: Layer::default(),
+    };
+
+    let serial = Layer::<Roads>::new(deps());
+    serial.ensure_loaded_in_bounds(region);
+
+    for threads in [1, 3, 8] {
+        let parallel = Layer::<Roads>::new(deps());
+        parallel.ensure_loaded_in_bounds_parallel(
+            region,
+            NonZeroUsize::new(threads).unwrap(),
+            deps,
+        );
+        assert!(parallel.is_loaded_in_grid_bounds(grid));
+        assert_eq!(
+            golden::checksums(&parallel, grid),
+            golden::checksums(&serial, grid),
+            "{threads} threads"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use layer_proc_gen::*;
+use persist::BackgroundWriter;
+use vec2::{Bounds, Point2d};
+
+#[derive(Clone, Default)]
+struct Cell;
+
+impl Chunk for Cell {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Disk;
+
+    fn compute_in_bounds(
+        writer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        writer
+            .0
+            .enqueue((index.map(|i| i.0), format!("{:?}", bounds.min)));
+        Cell
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+struct Disk(Arc<BackgroundWriter<(Point2d, String)>>);
+
+impl Dependencies for Disk {
+    fn debug(&self) -> Vec<&dyn debug::DynLayer> {
+        vec![]
+    }
+}
+
+#[test]
+fn writes_in_background() {
+    let dir = std::env::temp_dir().join(format!("layer-proc-gen-persist-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let generated = Arc::new(AtomicBool::new(false));
+
+    let writer = {
+        let dir = dir.clone();
+        let generated = generated.clone();
+        BackgroundWriter::new(64, move |(index, data): (Point2d, String)| {
+            // A very slow disk, that only starts writing once all chunks were generated.
+            let start = Instant::now();
+            while !generated.load(Ordering::Acquire) {
+                assert!(
+                    start.elapsed() < Duration::from_secs(10),
+                    "generation waited for disk"
+                );
+                std::thread::yield_now();
+            }
+            std::fs::write(dir.join(format!("{}_{}", index.x, index.y)), data).unwrap();
+        })
+    };
+    let writer = Arc::new(writer);
+    let layer = Layer::<Cell>::new(Disk(writer.clone()));
+    let region = Bounds {
+        min: point!(-3, -3),
+        max: point!(3, 3),
+    };
+    for index in region.iter() {
+        layer.get(index);
+    }
+    generated.store(true, Ordering::Release);
+    drop(layer);
+    Arc::into_inner(writer).unwrap().finish();
+
+    for index in region.iter() {
+        let bounds = Cell::bounds(index);
+        let path = dir.join(format!("{}_{}", index.x.0, index.y.0));
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            format!("{:?}", bounds.min)
+        );
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
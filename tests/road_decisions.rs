@@ -0,0 +1,37 @@
+use layer_proc_gen::{
+    generic_layers::{Euclidean, relative_neighborhood_graph},
+    vec2::{Bounds, Point2d},
+};
+
+#[test]
+fn rejected_by_closer_point() {
+    let a = Point2d::new(0, 0);
+    let b = Point2d::new(100, 0);
+    // Close to the middle between `a` and `b`, so closer to both of them than they are to each other.
+    let veto = Point2d::new(50, 10);
+    let center = Point2d::new(0, 0);
+    let chunks = Bounds::point(center)
+        .pad(Point2d::splat(1))
+        .iter()
+        .map(|index| {
+            (
+                index,
+                if index == center {
+                    vec![a, b, veto]
+                } else {
+                    vec![]
+                },
+            )
+        });
+    let mut rejected = vec![];
+    let roads = relative_neighborhood_graph(
+        Euclidean,
+        chunks,
+        center,
+        |&p| p,
+        |&a, &b| a.to(b),
+        |&a, &b, &veto| rejected.push((a.to(b), veto)),
+    );
+    assert_eq!(roads, [a.to(veto), b.to(veto)]);
+    assert_eq!(rejected, [(a.to(b), veto)]);
+}
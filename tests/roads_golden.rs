@@ -0,0 +1,33 @@
+//! Locks down the output of the layer stack of the `infinite_roads` example.
+
+use layer_proc_gen::*;
+use vec2::{Bounds, Point2d};
+
+#[path = "../examples/common/mod.rs"]
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+#[test]
+fn roads() {
+    let locations = Layer::<ReducedLocations>::default();
+    let roads = Layer::<Roads>::new(RoadsDeps {
+        intersections: locations.clone(),
+    });
+    // Same region as the `headless` example, the space between cities only has trees.
+    let city = locations
+        .cities
+        .get_grid_range(
+            Bounds::point(Point2d::splat(GridIndex::from_raw(0)))
+                .pad(Point2d::splat(GridIndex::from_raw(2))),
+        )
+        .flat_map(|c| c.points.into_iter())
+        .max_by_key(|city| city.size)
+        .unwrap();
+    let region = Bounds::point(city.center).pad(Point2d::splat(400));
+    // Generate the roads first, so the locations are computed the same way as in the example.
+    let roads = golden::checksums(&roads, Roads::bounds_to_grid(region));
+    let locations = golden::checksums(&locations, ReducedLocations::bounds_to_grid(region));
+    golden::assert_golden(&roads, "tests/golden/roads.txt");
+    golden::assert_golden(&locations, "tests/golden/locations.txt");
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "serde")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use layer_proc_gen::*;
+use vec2::Point2d;
+
+static COMPUTES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Default, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Tile(Point2d);
+
+impl Chunk for Tile {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    // Small enough that the test wraps around the grid and evicts chunks.
+    const GRID_SIZE: Point2d<u8> = Point2d::splat(1);
+    const GRID_OVERLAP: u8 = 2;
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        COMPUTES.fetch_add(1, Ordering::Relaxed);
+        Tile(index.map(|i| i.0))
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+fn index(x: i64, y: i64) -> GridPoint<Tile> {
+    Point2d::new(x, y).map(GridIndex::from_raw)
+}
+
+fn loaded(grid: &RollingGrid<Tile>) -> Vec<(Point2d, Tile)> {
+    let mut loaded: Vec<_> = grid
+        .iter_all_loaded()
+        .map(|(pos, chunk)| (pos.map(|i| i.0), chunk))
+        .collect();
+    loaded.sort_by_key(|(pos, _)| (pos.x, pos.y));
+    loaded
+}
+
+#[test]
+fn lru_round_trip() {
+    let grid = RollingGrid::<Tile>::default();
+    // (0, 0), (2, 0) and (-2, 2) all map to the same cell, so the first one gets evicted.
+    for (x, y) in [(0, 0), (1, 0), (2, 0), (1, 1), (-2, 2)] {
+        grid.get(index(x, y), &());
+    }
+    // Make (2, 0) the most recently used chunk of its cell.
+    grid.get(index(2, 0), &());
+    assert!(!grid.contains(index(0, 0)));
+    assert_eq!(grid.stats().occupied_slots, 4);
+
+    let json = serde_json::to_string(&grid).unwrap();
+    let restored: RollingGrid<Tile> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded(&restored), loaded(&grid));
+
+    // Restored chunks come from the cache.
+    let computes = COMPUTES.load(Ordering::Relaxed);
+    for (pos, chunk) in loaded(&grid) {
+        assert_eq!(restored.get(pos.map(GridIndex::from_raw), &()).0, chunk);
+    }
+    assert_eq!(COMPUTES.load(Ordering::Relaxed), computes);
+
+    // Both grids evict the least recently used chunk of the cell.
+    grid.get(index(4, 4), &());
+    restored.get(index(4, 4), &());
+    assert!(!grid.contains(index(-2, 2)));
+    assert_eq!(loaded(&restored), loaded(&grid));
+}
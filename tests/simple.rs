@@ -9,9 +9,14 @@ struct TheChunk(usize);
 
 impl Chunk for TheChunk {
     type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
     type Dependencies = ();
 
-    fn compute(_layer: &Self::Dependencies, _index: GridPoint<Self>) -> Self {
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
         TheChunk(0)
     }
 
@@ -32,6 +37,7 @@ deps! {
 
 impl Chunk for Player {
     type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
     type Dependencies = PlayerDeps;
 
     const GRID_SIZE: Point2d<u8> = Point2d::splat(0);
@@ -40,8 +46,12 @@ impl Chunk for Player {
 
     const SIZE: Point2d<u8> = Point2d::splat(0);
 
-    fn compute(deps: &Self::Dependencies, index: GridPoint<Self>) -> Self {
-        for _ in deps.layer.get_range(Self::bounds(index)) {}
+    fn compute_in_bounds(
+        deps: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        for _ in deps.layer.get_range(bounds) {}
         Player
     }
 
@@ -53,6 +63,7 @@ struct MapChunk;
 
 impl Chunk for MapChunk {
     type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
     type Dependencies = PlayerDeps;
 
     const SIZE: Point2d<u8> = Point2d::splat(0);
@@ -61,8 +72,12 @@ impl Chunk for MapChunk {
 
     const GRID_OVERLAP: u8 = 1;
 
-    fn compute(deps: &Self::Dependencies, index: GridPoint<Self>) -> Self {
-        for _ in deps.layer.get_range(Self::bounds(index)) {}
+    fn compute_in_bounds(
+        deps: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        for _ in deps.layer.get_range(bounds) {}
         MapChunk
     }
 
@@ -94,3 +109,359 @@ fn create_player() {
     let map = Layer::<MapChunk>::new(the_layer.clone());
     map.ensure_loaded_in_bounds(Bounds::point(player_pos));
 }
+
+#[derive(Clone, Default)]
+struct SharedChunk(Arc<Vec<i64>>);
+
+impl Chunk for SharedChunk {
+    type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        SharedChunk(Arc::new(vec![index.x.0, index.y.0]))
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+fn hold_chunk_past_layer() {
+    fn keep<T: 'static>(t: T) -> T {
+        t
+    }
+    let layer = Layer::<SharedChunk>::new(());
+    let index = Point2d { x: 4, y: -2 }.map(GridIndex::from_raw);
+    let chunk = keep(layer.get(index));
+    // Cached chunks share their data with the returned chunk.
+    assert!(Arc::ptr_eq(&chunk.0, &layer.get(index).0));
+    drop(layer);
+    assert_eq!(*chunk.0, [4, -2]);
+}
+
+#[test]
+fn shallow_with_loaded_deps() {
+    let deps = PlayerDeps::default();
+    let player = Layer::<Player>::new(deps.clone());
+    let index = Point2d { x: 42, y: 99 }.map(GridIndex::from_raw);
+    deps.layer.ensure_loaded_in_bounds(Player::bounds(index));
+    player.get_shallow(index);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "was not loaded before `get_shallow`"]
+fn shallow_without_deps() {
+    let player = Layer::<Player>::new(PlayerDeps::default());
+    player.get_shallow(Point2d { x: 42, y: 99 }.map(GridIndex::from_raw));
+}
+
+#[test]
+fn covering_grid_bounds() {
+    let grid = |x, y| Point2d { x, y }.map(GridIndex::<SharedChunk>::from_raw);
+    let world = Bounds {
+        min: Point2d::new(-1, 256),
+        max: Point2d::new(512, 700),
+    };
+    let covering = SharedChunk::covering_grid_bounds(world);
+    assert_eq!(covering.min, grid(-1, 1));
+    assert_eq!(covering.max, grid(2, 3));
+    // The chunk starting at `max.x` is not touched, as `max` is not part of the bounds.
+    assert_eq!(SharedChunk::bounds_to_grid(world), covering);
+
+    let index = grid(5, -7);
+    let covering = SharedChunk::covering_grid_bounds(SharedChunk::bounds(index));
+    assert_eq!(covering.iter().collect::<Vec<_>>(), [index]);
+
+    let point = Bounds::point(Point2d::new(-5, 3));
+    assert_eq!(
+        SharedChunk::covering_grid_bounds(point),
+        SharedChunk::bounds_to_grid(point)
+    );
+}
+
+#[derive(Clone, Default)]
+struct LineChunk(Vec<vec2::Line>);
+
+impl Chunk for LineChunk {
+    type LayerStore<T> = Arc<T>;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        // A single road starting in chunk (-1, 0) and reaching into chunk (0, 0).
+        if index == (Point2d { x: -1, y: 0 }.map(GridIndex::from_raw)) {
+            LineChunk(vec![Point2d::new(-100, 10).to(Point2d::new(200, 10))])
+        } else {
+            LineChunk(vec![])
+        }
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+fn clipped_lines_from_neighbors() {
+    let layer = Layer::<LineChunk>::new(());
+    let lines = |x| layer.clipped_lines(Point2d { x, y: 0 }.map(GridIndex::from_raw), |c| c.0);
+    assert_eq!(lines(-1), [Point2d::new(-100, 10).to(Point2d::new(0, 10))]);
+    assert_eq!(lines(0), [Point2d::new(0, 10).to(Point2d::new(200, 10))]);
+    assert_eq!(lines(1), []);
+}
+
+#[derive(Clone, Default, PartialEq, Debug)]
+struct ViaBounds(Bounds);
+
+impl Chunk for ViaBounds {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = ();
+
+    fn compute_in_bounds(
+        _layer: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        ViaBounds(bounds)
+    }
+
+    fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+fn compute_in_bounds() {
+    let layer = Layer::<ViaBounds>::new(());
+    for (x, y) in [(0, 0), (-3, 7), (100, -100)] {
+        let index = Point2d { x, y }.map(GridIndex::from_raw);
+        let expected = ViaBounds(ViaBounds::bounds(index));
+        assert_eq!(ViaBounds::compute(&(), index), expected);
+        assert_eq!(layer.get(index), expected);
+    }
+}
+
+macro_rules! coord_mode_chunk {
+    ($name:ident, $mode:ident) => {
+        #[derive(Clone, Default)]
+        struct $name;
+
+        impl Chunk for $name {
+            type LayerStore<T> = T;
+            type Grid = RollingGrid<Self>;
+            type Dependencies = ();
+            const COORD_MODE: vec2::CoordMode = vec2::CoordMode::$mode;
+
+            fn compute_in_bounds(
+                _layer: &Self::Dependencies,
+                _index: GridPoint<Self>,
+                _bounds: Bounds,
+            ) -> Self {
+                $name
+            }
+
+            fn clear(_layer: &Self::Dependencies, _index: GridPoint<Self>) {}
+        }
+    };
+}
+
+coord_mode_chunk!(Toroidal, Toroidal);
+coord_mode_chunk!(Bounded, Bounded);
+coord_mode_chunk!(Infinite, Infinite);
+
+#[test]
+fn coord_mode_at_limits() {
+    let last = Point2d::splat(i64::MAX >> 8);
+    let bounds = Toroidal::bounds(last.map(GridIndex::from_raw));
+    assert_eq!(bounds.max, Point2d::splat(i64::MIN));
+    let bounds = Bounded::bounds(last.map(GridIndex::from_raw));
+    assert_eq!(bounds.max, Point2d::splat(i64::MAX));
+    let neighbors = Bounded::moore_neighborhood(Point2d::splat(GridIndex::from_raw(i64::MAX)));
+    assert_eq!(neighbors[2][2], neighbors[1][1]);
+    let neighbors = Toroidal::moore_neighborhood(Point2d::splat(GridIndex::from_raw(i64::MAX)));
+    assert_eq!(
+        neighbors[2][2],
+        Point2d::splat(GridIndex::from_raw(i64::MIN))
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "overflow"]
+fn coord_mode_infinite_at_limits() {
+    Infinite::bounds(Point2d::splat(GridIndex::from_raw(i64::MAX >> 8)));
+}
+
+#[test]
+fn grid_point_constructors() {
+    let verbose = Point2d { x: 3, y: -4 }.map(GridIndex::<TheChunk>::from_raw);
+    assert_eq!(GridPoint::<TheChunk>::from_raw(3, -4), verbose);
+    assert_eq!(point!(TheChunk; 3, -4), verbose);
+    let inferred: GridPoint<TheChunk> = point!(3, -4);
+    assert_eq!(inferred, verbose);
+    assert_eq!(verbose.to_string(), "(3, -4) at (768, -1024)..(1024, -768)");
+}
+
+#[test]
+fn try_get_overflow() {
+    let last = Point2d::splat(i64::MAX >> 8);
+    let layer = Layer::<Infinite>::new(());
+    let err = layer.try_get(last.map(GridIndex::from_raw)).err().unwrap();
+    assert_eq!(
+        err,
+        LayerError::CoordinateOverflow {
+            layer: std::any::type_name::<Infinite>(),
+            index: last,
+        }
+    );
+    assert!(err.to_string().contains("outside the range of i64"));
+    assert!(layer.try_get(point!(-(1 << 40), 5)).is_ok());
+    assert!(
+        Layer::<Toroidal>::new(())
+            .try_get(last.map(GridIndex::from_raw))
+            .is_ok()
+    );
+
+    let bounds = Bounds::point(Point2d::splat(i64::MAX - 10)).pad(Point2d::splat(5));
+    assert_eq!(layer.try_ensure_loaded_in_bounds(bounds), Err(err));
+    let bounds = Bounds::point(Point2d::splat(1000)).pad(Point2d::splat(500));
+    assert_eq!(layer.try_ensure_loaded_in_bounds(bounds), Ok(()));
+    assert!(layer.is_loaded_in_bounds(bounds));
+}
+
+#[test]
+fn try_ensure_loaded_overlap_exceeded() {
+    let layer = Layer::<TheChunk>::new(());
+    let area = |width: i64| Bounds {
+        min: TheChunk::bounds(point!(0, 0)).min,
+        max: TheChunk::bounds(point!(width - 1, 0)).max,
+    };
+    // The default grid has 32 cells along each axis, each holding up to 3 chunks.
+    assert_eq!(layer.try_ensure_loaded_in_bounds(area(96)), Ok(()));
+    assert!(layer.is_loaded_in_bounds(area(96)));
+
+    let err = layer.try_ensure_loaded_in_bounds(area(97)).unwrap_err();
+    let LayerError::OverlapExceeded {
+        layer: name,
+        index,
+        span,
+    } = err.clone()
+    else {
+        panic!("{err}")
+    };
+    assert_eq!(name, std::any::type_name::<TheChunk>());
+    assert_eq!(span, Point2d::new(97, 1));
+    // The fourth chunk in the cell of the first one.
+    assert_eq!(index, Point2d::new(96, 0));
+    assert!(err.to_string().contains("evict"));
+    // Nothing was loaded or evicted.
+    assert!(layer.is_loaded_in_bounds(area(96)));
+    assert!(!layer.grid().contains(index.map(GridIndex::from_raw)));
+    assert_eq!(layer.grid().stats().computes, 96);
+
+    // Four chunks of a 33x33 area share the cell of its first chunk.
+    let layer = Layer::<TheChunk>::new(());
+    let area = Bounds {
+        min: TheChunk::bounds(point!(-1, -1)).min,
+        max: TheChunk::bounds(point!(31, 31)).max,
+    };
+    let Err(LayerError::OverlapExceeded { index, span, .. }) =
+        layer.try_ensure_loaded_in_bounds(area)
+    else {
+        panic!()
+    };
+    assert_eq!(span, Point2d::new(33, 33));
+    assert_eq!(index, Point2d::new(31, 31));
+    assert_eq!(layer.grid().stats().occupied_slots, 0);
+}
+
+/// Leaf layers don't need to implement `Chunk::clear`.
+#[derive(Clone, Default)]
+struct Leaf(Point2d);
+
+impl Chunk for Leaf {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = Seed;
+
+    fn compute_in_bounds(_seed: &Seed, _index: GridPoint<Self>, bounds: Bounds) -> Self {
+        Leaf(bounds.min)
+    }
+}
+
+#[test]
+fn leaf_without_clear() {
+    let layer = Layer::<Leaf>::new(Seed(0));
+    let bounds = Bounds {
+        min: Point2d::splat(0),
+        max: Point2d::splat(300),
+    };
+    layer.ensure_loaded_in_bounds(bounds);
+    assert!(layer.is_loaded_in_bounds(bounds));
+    layer.clear(bounds);
+    assert!(!layer.grid().contains(point!(0, 0)));
+    assert_eq!(layer.get(point!(1, 0)).0, Point2d::new(256, 0));
+}
+
+macro_rules! origin_chunk {
+    ($name:ident, $origin:expr) => {
+        #[derive(Clone, Default)]
+        struct $name(Vec<Point2d>);
+
+        impl Chunk for $name {
+            type LayerStore<T> = T;
+            type Grid = RollingGrid<Self>;
+            type Dependencies = Seed;
+            const SIZE: Point2d<u8> = Point2d::splat(5);
+            const ORIGIN: Point2d = $origin;
+
+            fn compute_in_bounds(seed: &Seed, index: GridPoint<Self>, _bounds: Bounds) -> Self {
+                let mut rng = rng::ChunkRng::new::<0, _>(index, *seed).stream(0);
+                Self(
+                    (0..4)
+                        .map(|_| Self::sample_point(index, &mut rng))
+                        .collect(),
+                )
+            }
+        }
+    };
+}
+
+origin_chunk!(AtZero, Point2d::splat(0));
+origin_chunk!(Offset, Point2d::new(1_000_003, -70_001));
+
+#[test]
+fn origin_shifts_world() {
+    let origin = Offset::ORIGIN;
+    let at_zero = Layer::<AtZero>::new(Seed(3));
+    let offset = Layer::<Offset>::new(Seed(3));
+    for (x, y) in [(0, 0), (-1, 0), (5, -7), (-100, 33)] {
+        let (a, b) = (point!(AtZero; x, y), point!(Offset; x, y));
+        assert_eq!(Offset::bounds(b).min, AtZero::bounds(a).min + origin);
+        let shifted: Vec<_> = at_zero.get(a).0.iter().map(|&p| p + origin).collect();
+        assert_eq!(offset.get(b).0, shifted);
+        for &p in &offset.get(b).0 {
+            assert_eq!(Offset::pos_to_grid(p), b);
+            assert_eq!(
+                Offset::pos_within_chunk(p),
+                AtZero::pos_within_chunk(p - origin)
+            );
+        }
+    }
+    // The origin itself is the `min` of chunk `(0, 0)`, the position before it belongs to chunk `(-1, -1)`.
+    assert_eq!(Offset::pos_to_grid(origin), point!(0, 0));
+    assert_eq!(
+        Offset::pos_to_grid(origin - Point2d::splat(1)),
+        point!(-1, -1)
+    );
+    assert_eq!(
+        Offset::covering_grid_bounds(Offset::bounds(point!(4, 2))),
+        Bounds::point(point!(4, 2))
+    );
+}
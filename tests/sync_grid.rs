@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use layer_proc_gen::*;
+use rng::ChunkRng;
+use vec2::{Bounds, Point2d};
+
+#[derive(Clone, Default, PartialEq, Debug)]
+struct Shared(Point2d);
+
+impl Chunk for Shared {
+    type LayerStore<T> = Arc<T>;
+    type Grid = SyncRollingGrid<Self>;
+    type Dependencies = Seed;
+
+    fn compute_in_bounds(
+        seed: &Self::Dependencies,
+        index: GridPoint<Self>,
+        bounds: Bounds,
+    ) -> Self {
+        Shared(bounds.sample(&mut ChunkRng::new::<0, _>(index, *seed).stream(0)))
+    }
+}
+
+#[test]
+fn concurrent_readers() {
+    let layer = Layer::<Shared>::new(Seed(9));
+    let region = Bounds {
+        min: Point2d::splat(-1000),
+        max: Point2d::splat(1000),
+    };
+    layer.ensure_loaded_in_bounds(region);
+    let grid = Shared::bounds_to_grid(region);
+    let expected: Vec<_> = layer.get_grid_range(grid).collect();
+    let serial = Layer::<Shared>::new(Seed(9));
+    let other = Bounds::point(Point2d::new(5000, 0)).pad(Point2d::splat(300));
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..10 {
+                    let chunks: Vec<_> = layer.get_grid_range(grid).collect();
+                    assert_eq!(chunks, expected);
+                }
+            });
+        }
+        // Computing chunks at the same time doesn't disturb the readers.
+        scope.spawn(|| {
+            let chunks: Vec<_> = layer.get_range(other).collect();
+            assert_eq!(chunks, serial.get_range(other).collect::<Vec<_>>());
+        });
+    });
+    assert!(layer.is_loaded_in_grid_bounds(grid));
+    assert_eq!(
+        layer.grid().iter_all_loaded().count(),
+        grid.iter().count() + Shared::bounds_to_grid(other).iter().count()
+    );
+}
+
+#[test]
+fn prefetch() {
+    let prefetcher = prefetch::Prefetcher::new(Arc::new(Layer::<Shared>::new(Seed(4))));
+    let ahead = Bounds::point(Point2d::new(2000, -300)).pad(Point2d::splat(500));
+    prefetcher.prefetch(ahead);
+    prefetcher.wait();
+
+    let layer = prefetcher.layer();
+    let serial = Layer::<Shared>::new(Seed(4));
+    for index in Shared::bounds_to_grid(ahead).iter() {
+        assert_eq!(
+            layer.get_with_freshness(index),
+            (serial.get(index), Freshness::Cached)
+        );
+    }
+    let behind = point!(Shared; -100, 0);
+    assert_eq!(layer.get_with_freshness(behind).1, Freshness::Created);
+}
+
+#[test]
+fn stats_from_other_threads() {
+    let layer = Layer::<Shared>::new(Seed(1));
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            layer.get(point!(0, 0));
+            layer.get(point!(0, 0));
+        });
+    });
+    let stats = std::thread::scope(|scope| scope.spawn(|| layer.grid().stats()).join().unwrap());
+    assert_eq!(
+        stats,
+        GridStats {
+            computes: 1,
+            cache_hits: 1,
+            evictions: 0,
+            occupied_slots: 1,
+        }
+    );
+
+    // All of these share a grid cell, so the fourth one evicts the first.
+    for x in 1..4 {
+        layer.get(point!(x * 32, 0));
+    }
+    assert_eq!(layer.grid().stats().evictions, 1);
+    layer.grid().reset_stats();
+    assert_eq!(
+        layer.grid().stats(),
+        GridStats {
+            occupied_slots: 3,
+            ..Default::default()
+        }
+    );
+}
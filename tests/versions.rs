@@ -0,0 +1,171 @@
+//! Recomputing cached chunks after their dependencies changed.
+
+use std::cell::Cell;
+
+use layer_proc_gen::*;
+
+thread_local! {
+    static COMPUTES: Cell<[usize; 4]> = const { Cell::new([0; 4]) };
+}
+
+fn computed(kind: usize) {
+    COMPUTES.with(|c| {
+        let mut counts = c.get();
+        counts[kind] += 1;
+        c.set(counts)
+    })
+}
+
+fn computes() -> [usize; 4] {
+    COMPUTES.get()
+}
+
+macro_rules! counted_chunk {
+    ($name:ident, $kind:expr, $deps:ty) => {
+        #[derive(Clone, Default)]
+        struct $name;
+
+        impl Chunk for $name {
+            type LayerStore<T> = T;
+            type Grid = RollingGrid<Self>;
+            type Dependencies = $deps;
+
+            fn compute_in_bounds(
+                _deps: &Self::Dependencies,
+                _index: GridPoint<Self>,
+                _bounds: Bounds,
+            ) -> Self {
+                computed($kind);
+                $name
+            }
+
+            fn clear(_deps: &Self::Dependencies, _index: GridPoint<Self>) {}
+        }
+
+        impl debug::Debug for $name {}
+    };
+}
+
+counted_chunk!(Locations, 0, ());
+counted_chunk!(Roads, 1, Layer<Locations>);
+counted_chunk!(Trees, 2, Layer<Locations>);
+counted_chunk!(Towns, 3, TownDeps);
+
+deps!(
+    struct TownDeps {
+        roads: Roads,
+        trees: Trees,
+    }
+);
+
+#[test]
+fn bump_recomputes_dependents() {
+    let roads = Layer::<Roads>::new(Layer::new(()));
+    let trees = Layer::<Trees>::new(Layer::new(()));
+    let index = point!(1, 2);
+    roads.get(index);
+    trees.get(index.same_index());
+    assert_eq!(computes(), [0, 1, 1, 0]);
+    assert_eq!(roads.get_with_freshness(index).1, Freshness::Cached);
+
+    let version = roads.version();
+    roads.bump_version();
+    assert_ne!(roads.version(), version);
+    assert_eq!(roads.get_with_freshness(index).1, Freshness::Cached);
+
+    // Only the chunks depending on the bumped layer are recomputed, and only once.
+    let version = roads.version();
+    let locations: &Layer<Locations> = &roads;
+    locations.bump_version();
+    assert_ne!(roads.version(), version);
+    assert_eq!(
+        roads.get_with_freshness(index).1,
+        Freshness::Created,
+        "stale chunk was returned"
+    );
+    assert_eq!(roads.get_with_freshness(index).1, Freshness::Cached);
+    assert_eq!(
+        trees.get_with_freshness(index.same_index()).1,
+        Freshness::Cached
+    );
+    assert_eq!(computes(), [0, 2, 1, 0]);
+    assert_eq!(roads.grid().stats().evictions, 0);
+}
+
+#[test]
+fn bump_through_deps_struct() {
+    let towns = Layer::<Towns>::new(TownDeps {
+        roads: Layer::new(Layer::new(())),
+        trees: Layer::new(Layer::new(())),
+    });
+    let index = point!(-3, 0);
+    towns.get(index);
+    assert_eq!(computes(), [0, 0, 0, 1]);
+    let locations: &Layer<Locations> = &towns.trees;
+    locations.bump_version();
+    assert_eq!(towns.get_with_freshness(index).1, Freshness::Created);
+    assert_eq!(computes(), [0, 0, 0, 2]);
+}
+
+#[test]
+fn bumps_of_different_deps_do_not_alias() {
+    let deps = || TownDeps {
+        roads: Layer::new(Layer::new(())),
+        trees: Layer::new(Layer::new(())),
+    };
+    let (a, b) = (deps(), deps());
+    assert_eq!(a.version(), b.version());
+    a.roads.bump_version();
+    b.trees.bump_version();
+    assert_ne!(a.version(), b.version());
+}
+
+thread_local! {
+    static VERSION_CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Hand-written dependencies that count how often their version is checked.
+struct FarmDeps(Layer<Locations>);
+
+impl Dependencies for FarmDeps {
+    fn debug(&self) -> Vec<&dyn debug::DynLayer> {
+        vec![&self.0]
+    }
+
+    fn version(&self) -> u64 {
+        VERSION_CALLS.set(VERSION_CALLS.get() + 1);
+        self.0.version()
+    }
+}
+
+#[derive(Clone, Default)]
+struct Farms;
+
+impl Chunk for Farms {
+    type LayerStore<T> = T;
+    type Grid = RollingGrid<Self>;
+    type Dependencies = FarmDeps;
+
+    fn compute_in_bounds(
+        _deps: &Self::Dependencies,
+        _index: GridPoint<Self>,
+        _bounds: Bounds,
+    ) -> Self {
+        Farms
+    }
+
+    fn clear(_deps: &Self::Dependencies, _index: GridPoint<Self>) {}
+}
+
+#[test]
+fn cache_hits_do_not_check_dependencies() {
+    let farms = Layer::<Farms>::new(FarmDeps(Layer::new(())));
+    for _ in 0..100 {
+        farms.get(point!(2, 2));
+    }
+    // Bumps in the other tests running at the same time make the grid check again.
+    assert!(VERSION_CALLS.get() < 10, "{}", VERSION_CALLS.get());
+
+    farms.0.bump_version();
+    assert_eq!(farms.get_with_freshness(point!(2, 2)).1, Freshness::Created);
+}